@@ -0,0 +1,332 @@
+//! Derive macros for `fastbuf::Encode` and `fastbuf::Decode`, use them through fastbuf's
+//! `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Generics, Ident, LitInt, Type,
+};
+
+/// Derives `fastbuf::Encode`, fields are written in declaration order.
+///
+/// Enums write the variant index first, as `u8` or as `u16` past 256 variants.
+#[proc_macro_derive(Encode)]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `fastbuf::Decode`, reading what `#[derive(Encode)]` writes.
+#[proc_macro_derive(Decode)]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Variant {
+    path: TokenStream2,
+    pattern: TokenStream2,
+    bindings: Vec<Ident>,
+    types: Vec<Type>,
+    tag: Option<LitInt>,
+}
+
+struct Shape {
+    variants: Vec<Variant>,
+    tag_type: Option<TokenStream2>,
+}
+
+impl Shape {
+    fn new(input: &DeriveInput) -> syn::Result<Self> {
+        match &input.data {
+            Data::Struct(data) => Ok(Self {
+                variants: vec![Variant::new(quote!(Self), &data.fields, None)],
+                tag_type: None,
+            }),
+            Data::Enum(data) => {
+                let count = data.variants.len();
+                let tag_type = match count {
+                    0 => {
+                        return Err(Error::new_spanned(
+                            &input.ident,
+                            "can't derive for an enum without variants",
+                        ))
+                    }
+                    1..=0x100 => "u8",
+                    0x101..=0x10000 => "u16",
+                    _ => return Err(Error::new_spanned(&input.ident, "too many variants")),
+                };
+                let variants = data
+                    .variants
+                    .iter()
+                    .enumerate()
+                    .map(|(index, variant)| {
+                        let ident = &variant.ident;
+                        let tag = LitInt::new(&format!("{index}{tag_type}"), Span::call_site());
+                        Variant::new(quote!(Self::#ident), &variant.fields, Some(tag))
+                    })
+                    .collect();
+                let tag_type = Ident::new(tag_type, Span::call_site());
+                Ok(Self {
+                    variants,
+                    tag_type: Some(quote!(#tag_type)),
+                })
+            }
+            Data::Union(data) => Err(Error::new_spanned(
+                data.union_token,
+                "can't derive for a union",
+            )),
+        }
+    }
+
+    /// `ENCODED_SIZE` expression in terms of the fields' sizes for `trait_path`.
+    fn size(&self, trait_path: &TokenStream2) -> TokenStream2 {
+        let sizes = self.variants.iter().map(|variant| {
+            let types = &variant.types;
+            quote!(::fastbuf::sum_encoded_sizes(&[#(<#types as #trait_path>::ENCODED_SIZE),*]))
+        });
+        match &self.tag_type {
+            None => quote!(#(#sizes)*),
+            Some(tag_type) => quote! {
+                ::fastbuf::sum_encoded_sizes(&[
+                    <#tag_type as #trait_path>::ENCODED_SIZE,
+                    ::fastbuf::same_encoded_size(&[#(#sizes),*]),
+                ])
+            },
+        }
+    }
+}
+
+impl Variant {
+    fn new(path: TokenStream2, fields: &Fields, tag: Option<LitInt>) -> Self {
+        let bindings: Vec<Ident> = (0..fields.len())
+            .map(|index| format_ident!("field_{index}"))
+            .collect();
+        let types = fields.iter().map(|field| field.ty.clone()).collect();
+        let pattern = match fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote!({ #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!((#(#bindings),*)),
+            Fields::Unit => quote!(),
+        };
+        Self {
+            path,
+            pattern,
+            bindings,
+            types,
+            tag,
+        }
+    }
+}
+
+fn add_bounds(generics: &Generics, bound: &TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    let params: Vec<Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
+
+fn expand_encode(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let trait_path = quote!(::fastbuf::Encode);
+    let shape = Shape::new(&input)?;
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, &trait_path);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let size = shape.size(&trait_path);
+
+    let encode_arms = shape.variants.iter().map(|variant| {
+        let Variant {
+            path,
+            pattern,
+            bindings,
+            tag,
+            ..
+        } = variant;
+        let tag = tag
+            .as_ref()
+            .map(|tag| quote!(::fastbuf::Encode::encode(&#tag, buf)?;));
+        quote! {
+            #path #pattern => {
+                #tag
+                #(::fastbuf::Encode::encode(#bindings, buf)?;)*
+            }
+        }
+    });
+
+    let encode_fixed_arms = shape.variants.iter().map(|variant| {
+        let Variant {
+            path,
+            pattern,
+            bindings,
+            types,
+            tag,
+        } = variant;
+        let tag = tag.as_ref().map(|tag| {
+            let tag_type = shape.tag_type.as_ref().unwrap();
+            quote! {
+                let (head, dst) = dst.split_at_mut(::fastbuf::fixed_size(<#tag_type as #trait_path>::ENCODED_SIZE));
+                ::fastbuf::Encode::encode_fixed(&#tag, head);
+            }
+        });
+        quote! {
+            #path #pattern => {
+                #tag
+                #(
+                    let (head, dst) = dst.split_at_mut(::fastbuf::fixed_size(<#types as #trait_path>::ENCODED_SIZE));
+                    ::fastbuf::Encode::encode_fixed(#bindings, head);
+                )*
+                debug_assert!(dst.is_empty());
+            }
+        }
+    });
+
+    // `try_write_fast` needs the size as a const which generic types can't provide,
+    // and a non-zero one.
+    let fast_path = input.generics.params.is_empty().then(|| {
+        quote! {
+            const SIZE: usize = ::fastbuf::fixed_size(<#name as #trait_path>::ENCODED_SIZE);
+            if <Self as #trait_path>::ENCODED_SIZE.is_some() {
+                if SIZE == 0 {
+                    return ::core::result::Result::Ok(());
+                }
+                let mut bytes = [0; SIZE];
+                ::fastbuf::Encode::encode_fixed(self, &mut bytes);
+                return ::fastbuf::WriteBuf::try_write_fast::<SIZE>(buf, &bytes);
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            const ENCODED_SIZE: ::core::option::Option<usize> = #size;
+
+            #[inline]
+            fn encode<W: ::fastbuf::WriteBuf<u8> + ?Sized>(
+                &self,
+                buf: &mut W,
+            ) -> ::core::result::Result<(), ::fastbuf::WriteBufferError> {
+                #fast_path
+                match self {
+                    #(#encode_arms)*
+                }
+                ::core::result::Result::Ok(())
+            }
+
+            #[inline]
+            fn encode_fixed(&self, dst: &mut [u8]) {
+                match self {
+                    #(#encode_fixed_arms)*
+                }
+            }
+        }
+    })
+}
+
+fn expand_decode(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let trait_path = quote!(::fastbuf::Decode);
+    let shape = Shape::new(&input)?;
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, &trait_path);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let size = shape.size(&trait_path);
+
+    let construct = |variant: &Variant, fixed: bool| {
+        let Variant {
+            path,
+            pattern,
+            bindings,
+            types,
+            ..
+        } = variant;
+        let fields = if fixed {
+            quote! {
+                #(
+                    let (head, src) = src.split_at(::fastbuf::fixed_size(<#types as #trait_path>::ENCODED_SIZE));
+                    let #bindings = <#types as #trait_path>::decode_fixed(head)?;
+                )*
+                debug_assert!(src.is_empty());
+            }
+        } else {
+            quote!(#(let #bindings = <#types as #trait_path>::decode(buf)?;)*)
+        };
+        quote! {
+            #fields
+            ::core::result::Result::Ok(#path #pattern)
+        }
+    };
+
+    let (decode, decode_fixed) = match &shape.tag_type {
+        None => (
+            construct(&shape.variants[0], false),
+            construct(&shape.variants[0], true),
+        ),
+        Some(tag_type) => {
+            let tags: Vec<_> = shape.variants.iter().map(|v| &v.tag).collect();
+            let decode_arms = shape.variants.iter().map(|v| construct(v, false));
+            let decode_fixed_arms = shape.variants.iter().map(|v| construct(v, true));
+            let invalid_tag = quote! {
+                tag => ::core::result::Result::Err(::fastbuf::DecodeError::InvalidTag(tag as u32)),
+            };
+            (
+                quote! {
+                    match <#tag_type as #trait_path>::decode(buf)? {
+                        #(#tags => { #decode_arms })*
+                        #invalid_tag
+                    }
+                },
+                quote! {
+                    let (head, src) = src.split_at(::fastbuf::fixed_size(<#tag_type as #trait_path>::ENCODED_SIZE));
+                    match <#tag_type as #trait_path>::decode_fixed(head)? {
+                        #(#tags => { #decode_fixed_arms })*
+                        #invalid_tag
+                    }
+                },
+            )
+        }
+    };
+
+    let fast_path = input.generics.params.is_empty().then(|| {
+        quote! {
+            const SIZE: usize = ::fastbuf::fixed_size(<#name as #trait_path>::ENCODED_SIZE);
+            if <Self as #trait_path>::ENCODED_SIZE.is_some() {
+                if SIZE == 0 {
+                    return <Self as #trait_path>::decode_fixed(&[]);
+                }
+                let bytes = ::fastbuf::ReadBufExt::try_read_array::<SIZE>(buf)?;
+                return <Self as #trait_path>::decode_fixed(&bytes);
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            const ENCODED_SIZE: ::core::option::Option<usize> = #size;
+
+            #[inline]
+            fn decode<R: ::fastbuf::ReadBuf<u8> + ?Sized>(
+                buf: &mut R,
+            ) -> ::core::result::Result<Self, ::fastbuf::DecodeError> {
+                #fast_path
+                #decode
+            }
+
+            #[inline]
+            fn decode_fixed(src: &[u8]) -> ::core::result::Result<Self, ::fastbuf::DecodeError> {
+                #decode_fixed
+            }
+        }
+    })
+}
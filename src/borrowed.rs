@@ -0,0 +1,399 @@
+use core::{
+    fmt::Debug,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut, NonNull},
+};
+use std::alloc::Allocator;
+
+use crate::{
+    buffer::ALLOC, unsafe_wild_copy, Buf, BufIndex, Buffer, Chunk, ReadBuf, ReadBufMut, WriteBuf,
+    WriteBufferError,
+};
+
+/// Buffer over memory it doesn't own, e.g. a region handed over through FFI or a scratch slice
+/// whose length is only known at runtime.
+///
+/// Both positions are stored as `L`, which defaults to `usize` so that any slice fits.
+pub type BorrowedBuffer<'a, T, A = ALLOC, L = usize> =
+    Buffer<BorrowedChunk<'a, T>, A, BorrowedChunk<'a, T>, L>;
+
+/// Chunk borrowing `&'a mut [T]` or `&'a mut [MaybeUninit<T>]`, tracking its initialized prefix
+/// like [`UninitChunk`](crate::UninitChunk).
+pub struct BorrowedChunk<'a, T> {
+    ptr: NonNull<T>,
+    capacity: usize,
+    init: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+unsafe impl<T: Send> Send for BorrowedChunk<'_, T> {}
+unsafe impl<T: Sync> Sync for BorrowedChunk<'_, T> {}
+
+impl<'a, T> BorrowedChunk<'a, T> {
+    #[inline(always)]
+    pub fn from_slice(slice: &'a mut [T]) -> Self {
+        let capacity = slice.len();
+        Self::new(slice.as_mut_ptr(), capacity, capacity)
+    }
+
+    #[inline(always)]
+    pub fn from_uninit_slice(slice: &'a mut [MaybeUninit<T>]) -> Self {
+        Self::new(slice.as_mut_ptr() as *mut T, slice.len(), 0)
+    }
+
+    #[inline(always)]
+    fn new(ptr: *mut T, capacity: usize, init: usize) -> Self {
+        Self {
+            ptr: NonNull::new(ptr).unwrap(),
+            capacity,
+            init,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Length of the initialized prefix.
+    #[inline(always)]
+    pub const fn init_len(&self) -> usize {
+        self.init
+    }
+}
+
+impl<'a, T> From<&'a mut [T]> for BorrowedChunk<'a, T> {
+    #[inline(always)]
+    fn from(slice: &'a mut [T]) -> Self {
+        Self::from_slice(slice)
+    }
+}
+
+impl<'a, T> From<&'a mut [MaybeUninit<T>]> for BorrowedChunk<'a, T> {
+    #[inline(always)]
+    fn from(slice: &'a mut [MaybeUninit<T>]) -> Self {
+        Self::from_uninit_slice(slice)
+    }
+}
+
+impl<T> Chunk<T> for BorrowedChunk<'_, T> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        unsafe { &*slice_from_raw_parts(self.ptr.as_ptr(), self.init) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { &mut *slice_from_raw_parts_mut(self.ptr.as_ptr(), self.init) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    #[inline(always)]
+    unsafe fn set_init(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity);
+        if len > self.init {
+            self.init = len;
+        }
+    }
+}
+
+impl<'a, T, A: Allocator, L: BufIndex> BorrowedBuffer<'a, T, A, L> {
+    /// Buffer over `slice`, starting out empty.
+    #[inline(always)]
+    pub fn from_slice(slice: &'a mut [T]) -> Self {
+        Self::from_chunk(BorrowedChunk::from_slice(slice))
+    }
+
+    /// Buffer over `slice`, starting out empty.
+    #[inline(always)]
+    pub fn from_uninit_slice(slice: &'a mut [MaybeUninit<T>]) -> Self {
+        Self::from_chunk(BorrowedChunk::from_uninit_slice(slice))
+    }
+
+    /// Panics when the capacity of `chunk` doesn't fit `L`.
+    #[inline(always)]
+    pub fn from_chunk(chunk: BorrowedChunk<'a, T>) -> Self {
+        assert!(chunk.capacity() <= L::MAX, "capacity overflow");
+        Self {
+            chunk,
+            filled_pos: L::from_usize(0),
+            pos: L::from_usize(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> Chunk<T> for BorrowedBuffer<'_, T, A, L> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        unsafe { self.get_continuous(self.remaining()) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { self.get_continuous_mut(self.remaining()) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.chunk.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.chunk.as_mut_ptr()
+    }
+}
+
+impl<T: Copy, A: Allocator, L: BufIndex> Buf<T> for BorrowedBuffer<'_, T, A, L> {
+    #[inline(always)]
+    fn clear(&mut self) {
+        self.filled_pos = L::from_usize(0);
+        self.pos = L::from_usize(0);
+    }
+
+    #[inline(always)]
+    fn compact(&mut self) {
+        let pos = self.pos.to_usize();
+        let len = self.filled_pos.to_usize() - pos;
+        if pos != 0 {
+            let ptr = self.chunk.as_mut_ptr();
+            unsafe { ptr.wrapping_add(pos).copy_to(ptr, len) };
+            self.filled_pos = L::from_usize(len);
+            self.pos = L::from_usize(0);
+        }
+    }
+}
+
+impl<T: Copy, A: Allocator, L: BufIndex> WriteBuf<T> for BorrowedBuffer<'_, T, A, L> {
+    #[inline(always)]
+    fn write(&mut self, data: &[T]) {
+        self.try_write(data).expect("buffer overflow")
+    }
+
+    #[inline(always)]
+    fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
+        let filled_pos = self.filled_pos.to_usize();
+        let new_filled_pos = filled_pos + data.len();
+        if new_filled_pos <= self.chunk.capacity() {
+            self.filled_pos = L::from_usize(new_filled_pos);
+            let dst = self.chunk.as_mut_ptr().wrapping_add(filled_pos);
+            unsafe {
+                data.as_ptr().copy_to_nonoverlapping(dst, data.len());
+                self.chunk.set_init(new_filled_pos);
+            }
+            Ok(())
+        } else {
+            Err(WriteBufferError::BufferFull)
+        }
+    }
+
+    #[inline(always)]
+    fn try_write_fast<const LEN: usize>(
+        &mut self,
+        data: &[T; LEN],
+    ) -> Result<(), WriteBufferError> {
+        let filled_pos = self.filled_pos.to_usize();
+        let new_filled_pos = filled_pos + LEN;
+        if new_filled_pos <= self.chunk.capacity() {
+            let src_ptr = data.as_ptr();
+            let dst_ptr = self.chunk.as_mut_ptr().wrapping_add(filled_pos);
+            self.filled_pos = L::from_usize(new_filled_pos);
+            unsafe {
+                unsafe_wild_copy!([T; LEN], src_ptr, dst_ptr, LEN);
+                self.chunk.set_init(new_filled_pos);
+            }
+            Ok(())
+        } else {
+            Err(WriteBufferError::BufferFull)
+        }
+    }
+
+    #[inline(always)]
+    fn remaining_space(&self) -> usize {
+        self.chunk.capacity() - self.filled_pos.to_usize()
+    }
+
+    #[inline(always)]
+    fn filled_pos(&self) -> usize {
+        self.filled_pos.to_usize()
+    }
+
+    #[inline(always)]
+    unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+        self.filled_pos = L::from_usize(filled_pos);
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.chunk.capacity()
+    }
+
+    #[inline(always)]
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let filled_pos = self.filled_pos.to_usize();
+        let ptr = self.chunk.as_mut_ptr().wrapping_add(filled_pos) as *mut MaybeUninit<T>;
+        unsafe { &mut *slice_from_raw_parts_mut(ptr, self.chunk.capacity() - filled_pos) }
+    }
+
+    #[inline(always)]
+    unsafe fn commit(&mut self, len: usize) {
+        debug_assert!(len <= self.remaining_space());
+        let filled_pos = self.filled_pos.to_usize() + len;
+        self.filled_pos = L::from_usize(filled_pos);
+        unsafe { self.chunk.set_init(filled_pos) };
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> ReadBuf<T> for BorrowedBuffer<'_, T, A, L> {
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[T] {
+        let pos = self.pos.to_usize();
+        let slice_len = core::cmp::min(len, self.filled_pos.to_usize() - pos);
+        self.pos = L::from_usize(pos + slice_len);
+        unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(pos), slice_len) }
+    }
+
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[T] {
+        let pos = self.pos.to_usize();
+        let slice_len = core::cmp::min(len, self.filled_pos.to_usize() - pos);
+        unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(pos), slice_len) }
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.filled_pos.to_usize() - self.pos.to_usize()
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        let pos = self.pos.to_usize();
+        self.pos = L::from_usize(core::cmp::min(self.filled_pos.to_usize(), pos + len));
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos.to_usize()
+    }
+
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        self.pos = L::from_usize(pos);
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> ReadBufMut<T> for BorrowedBuffer<'_, T, A, L> {
+    #[inline(always)]
+    unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+        let pos = self.pos.to_usize();
+        let slice_len = core::cmp::min(len, self.filled_pos.to_usize() - pos);
+        unsafe {
+            &mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(pos), slice_len)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Allocator, L: BufIndex> std::io::Write for BorrowedBuffer<'_, u8, A, L> {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.try_write(buf)
+            .map_err(|_| std::io::Error::other("write buffer failed"))?;
+        Ok(buf.len())
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Allocator, L: BufIndex> std::io::Read for BorrowedBuffer<'_, u8, A, L> {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let data = ReadBuf::read(self, buf.len());
+        let read_length = data.len();
+        buf[..read_length].copy_from_slice(data);
+        Ok(read_length)
+    }
+}
+
+impl<T: Debug, A: Allocator, L: BufIndex> Debug for BorrowedBuffer<'_, T, A, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        unsafe { self.get_continuous(self.remaining()) }.fmt(f)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{ReadBufExt, ReadToBuf, WriteBufExt};
+
+    fn write_message(buf: &mut impl WriteBuf<u8>, body: &[u8]) -> Result<(), WriteBufferError> {
+        buf.try_write_u16_be(body.len() as u16)?;
+        buf.try_write(body)
+    }
+
+    #[test]
+    fn test_runtime_capacity() {
+        let mut scratch = vec![0xffu8; 6];
+        let mut buffer = BorrowedBuffer::<u8>::from_slice(&mut scratch);
+        assert_eq!(buffer.capacity(), 6);
+        write_message(&mut buffer, b"hey").unwrap();
+        assert_eq!(
+            write_message(&mut buffer, b"hey"),
+            Err(WriteBufferError::BufferFull)
+        );
+        assert_eq!(buffer.try_read_u16_be(), Ok(3));
+        buffer.compact();
+        buffer.try_write_fast(b"yo").unwrap();
+        assert_eq!(format!("{buffer:?}"), format!("{:?}", b"heyyo"));
+        assert_eq!(&scratch[..5], b"heyyo");
+    }
+
+    #[test]
+    fn test_uninit_slice() {
+        let mut storage = [MaybeUninit::<u8>::uninit(); 8];
+        let mut buffer: BorrowedBuffer<u8> = Buffer::from_uninit_slice(&mut storage);
+        assert_eq!(buffer.chunk.as_slice(), &[]);
+        buffer.write(b"ab");
+        let mut reader = &b"cdefghijk"[..];
+        assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 6);
+        assert_eq!(buffer.chunk.init_len(), 8);
+        assert_eq!(buffer.read(8), b"abcdefgh");
+        assert_eq!(buffer.remaining_space(), 0);
+    }
+
+    #[test]
+    fn test_index_type() {
+        let mut scratch = vec![0u8; 0x20000];
+        let mut buffer = BorrowedBuffer::<u8>::from_slice(&mut scratch);
+        buffer.write(&[1; 0x1ffff]);
+        buffer.write(&[2]);
+        assert_eq!(buffer.remaining(), 0x20000);
+        assert_eq!(buffer.read(0x20000)[0x1ffff], 2);
+
+        let mut small = [0u8; 255];
+        let buffer = BorrowedBuffer::<u8, ALLOC, u8>::from_slice(&mut small);
+        assert_eq!(buffer.capacity(), 255);
+        let mut large = [0u8; 256];
+        assert!(std::panic::catch_unwind(move || {
+            BorrowedBuffer::<u8, ALLOC, u8>::from_slice(&mut large);
+        })
+        .is_err());
+    }
+}
@@ -1,40 +1,56 @@
-use core::{fmt::Debug, marker::PhantomData, ptr::slice_from_raw_parts};
+use core::{fmt::Debug, marker::PhantomData, mem::MaybeUninit, ptr::slice_from_raw_parts};
+#[cfg(feature = "std")]
+use std::io::BorrowedBuf;
 use std::{alloc::Allocator, ptr::slice_from_raw_parts_mut};
 
 use crate::{
-    const_min, declare_const_fn, declare_const_impl, unsafe_wild_copy, Buf, Chunk, ChunkBuilder,
-    ReadBuf, ReadToBuf, WriteBuf, WriteBufferError,
+    const_min, declare_const_fn, declare_const_impl, unsafe_wild_copy, Buf, BufIndex, Chunk,
+    ChunkBuilder, ReadBuf, ReadBufMut, ReadToBuf, ReadToBufError, UninitChunk, WriteBuf,
+    WriteBufferError, WriteFromBuf, WriteFromBufError,
 };
 
 #[cfg(feature = "std")]
-type ALLOC = std::alloc::Global;
+pub(crate) type ALLOC = std::alloc::Global;
 #[cfg(not(feature = "std"))]
-type ALLOC = crate::EmptyAlloc;
+pub(crate) type ALLOC = crate::EmptyAlloc;
 
 #[cfg(feature = "std")]
 pub type BoxedBuffer<S, A = ALLOC> = Buffer<S, A, Box<S>>;
 
-pub struct Buffer<S, A: Allocator = ALLOC, C = S> {
-    chunk: C,
-    filled_pos: LenUint,
-    pos: LenUint,
-    _marker: PhantomData<(A, S)>,
+/// [`Buffer`] that doesn't initialize its chunk up front, see [`UninitChunk`].
+pub type UninitBuffer<T, const N: usize, A = ALLOC> = Buffer<[T; N], A, UninitChunk<T, N>>;
+
+/// Linear buffer over the chunk `C`, reading from `pos` up to `filled_pos`.
+///
+/// Both positions are stored as `L`, see [`BufIndex`].
+pub struct Buffer<S, A: Allocator = ALLOC, C = S, L = LenUint> {
+    pub(crate) chunk: C,
+    pub(crate) filled_pos: L,
+    pub(crate) pos: L,
+    pub(crate) _marker: PhantomData<(A, S)>,
 }
 
+/// Default [`BufIndex`] of [`Buffer`] and [`RingBuffer`](crate::RingBuffer).
 #[cfg(target_pointer_width = "64")]
 pub type LenUint = u32;
+/// Default [`BufIndex`] of [`Buffer`] and [`RingBuffer`](crate::RingBuffer).
 #[cfg(target_pointer_width = "32")]
 pub type LenUint = u16;
 
 #[cfg(feature = "impl_copy_for_buffer")]
-impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T> + Copy + Clone> Copy
-    for Buffer<[T; N], A, C>
+impl<
+        T: Copy,
+        const N: usize,
+        A: Allocator,
+        C: ChunkBuilder<A> + Chunk<T> + Copy + Clone,
+        L: BufIndex,
+    > Copy for Buffer<[T; N], A, C, L>
 {
 }
 
 declare_const_impl! {
-    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> Buffer<[T; N], A, C>),
-    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>> Buffer<[T; N], A, C>) {
+    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Buffer<[T; N], A, C, L>),
+    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> Buffer<[T; N], A, C, L>) {
         declare_const_fn! {
             #[inline(always)]
             pub fn new_in(alloc: A) -> Self {
@@ -87,43 +103,60 @@ declare_const_impl! {
 }
 
 declare_const_impl! {
-    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> ChunkBuilder<A> for Buffer<[T; N], A, C>),
-    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>> const ChunkBuilder<A> for Buffer<[T; N], A, C>) {
+    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> ChunkBuilder<A> for Buffer<[T; N], A, C, L>),
+    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const ChunkBuilder<A> for Buffer<[T; N], A, C, L>) {
         #[inline(always)]
         fn new_in(alloc: A) -> Self {
+            const { assert!(N <= L::MAX, "capacity doesn't fit the index type") };
             Self {
                 chunk: C::new_in(alloc),
-                filled_pos: 0,
-                pos: 0,
+                filled_pos: L::from_usize(0),
+                pos: L::from_usize(0),
                 _marker: PhantomData,
             }
         }
 
         #[inline(always)]
         fn new() -> Self {
+            const { assert!(N <= L::MAX, "capacity doesn't fit the index type") };
             Self {
                 chunk: C::new(),
-                filled_pos : 0,
-                pos: 0,
+                filled_pos: L::from_usize(0),
+                pos: L::from_usize(0),
                 _marker: PhantomData
             }
         }
 
         #[inline(always)]
         fn new_zeroed() -> Self {
+            const { assert!(N <= L::MAX, "capacity doesn't fit the index type") };
             Self {
                 chunk: C::new_zeroed(),
-                filled_pos : 0,
-                pos: 0,
+                filled_pos: L::from_usize(0),
+                pos: L::from_usize(0),
                 _marker: PhantomData
             }
         }
     }
 }
 
+impl<T, A: Allocator, const N: usize, C, L: BufIndex> Buffer<[T; N], A, C, L> {
+    /// Empty buffer over `chunk`, for chunks that aren't built through [`ChunkBuilder`].
+    #[inline(always)]
+    pub(crate) fn from_chunk(chunk: C) -> Self {
+        const { assert!(N <= L::MAX, "capacity doesn't fit the index type") };
+        Self {
+            chunk,
+            filled_pos: L::from_usize(0),
+            pos: L::from_usize(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
 declare_const_impl! {
-    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> +  Chunk<T>> Chunk<T> for Buffer<[T; N], A, C>),
-    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>> const Chunk<T> for Buffer<[T; N], A, C>) {
+    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> +  Chunk<T>, L: BufIndex> Chunk<T> for Buffer<[T; N], A, C, L>),
+    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const Chunk<T> for Buffer<[T; N], A, C, L>) {
         #[inline(always)]
         fn as_slice(&self) -> &[T] {
             unsafe { self.get_continuous(self.remaining()) }
@@ -146,8 +179,8 @@ declare_const_impl! {
     }
 }
 
-impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T> + Clone> Clone
-    for Buffer<[T; N], A, C>
+impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T> + Clone, L: BufIndex> Clone
+    for Buffer<[T; N], A, C, L>
 {
     fn clone(&self) -> Self {
         Self {
@@ -160,36 +193,40 @@ impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T> + Clone> Clo
 }
 
 declare_const_impl! {
-    (impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>> Buf<T> for Buffer<[T; N], A, C>),
-    (impl<T: Copy, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>> const Buf<T> for Buffer<[T; N], A, C>) {
+    (impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Buf<T> for Buffer<[T; N], A, C, L>),
+    (impl<T: Copy, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const Buf<T> for Buffer<[T; N], A, C, L>) {
         #[inline(always)]
         fn clear(&mut self) {
-            self.filled_pos = 0;
-            self.pos = 0;
+            self.filled_pos = L::from_usize(0);
+            self.pos = L::from_usize(0);
+        }
+
+        #[inline(always)]
+        fn compact(&mut self) {
+            let pos = self.pos.to_usize();
+            let len = self.filled_pos.to_usize() - pos;
+            if pos != 0 {
+                let ptr = self.chunk.as_mut_ptr();
+                unsafe { ptr.wrapping_add(pos).copy_to(ptr, len) };
+                self.filled_pos = L::from_usize(len);
+                self.pos = L::from_usize(0);
+            }
         }
     }
 }
 
 declare_const_impl! {
-    (impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>> WriteBuf<T> for Buffer<[T; N], A, C>),
-    (impl<T: Copy, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>> const WriteBuf<T> for Buffer<[T; N], A, C>) {
+    (impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> WriteBuf<T> for Buffer<[T; N], A, C, L>),
+    (impl<T: Copy, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const WriteBuf<T> for Buffer<[T; N], A, C, L>) {
         #[inline(always)]
         fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
-            let filled_pos = self.filled_pos as usize;
+            let filled_pos = self.filled_pos.to_usize();
             let new_filled_pos = filled_pos + data.len();
             if new_filled_pos <= N {
-                self.filled_pos = new_filled_pos as LenUint;
-                #[cfg(not(feature = "const-trait"))]
+                self.filled_pos = L::from_usize(new_filled_pos);
                 unsafe {
-                    self.chunk
-                        .as_mut_slice()
-                        .get_unchecked_mut(filled_pos..new_filled_pos)
-                        .copy_from_slice(data);
-                }
-
-                #[cfg(feature = "const-trait")]
-                unsafe {
-                    (&mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(filled_pos),data.len())).copy_from_slice(data);
+                    data.as_ptr().copy_to_nonoverlapping(self.chunk.as_mut_ptr().wrapping_add(filled_pos), data.len());
+                    self.chunk.set_init(new_filled_pos);
                 }
                 Ok(())
             } else {
@@ -199,13 +236,16 @@ declare_const_impl! {
 
         #[inline(always)]
         fn try_write_fast<const LEN: usize>(&mut self, data: &[T; LEN]) -> Result<(), WriteBufferError> {
-            let filled_pos = self.filled_pos as usize;
+            let filled_pos = self.filled_pos.to_usize();
             let new_filled_pos = filled_pos + data.len();
             if new_filled_pos <= N {
                 let src_ptr = data.as_ptr();
-                let dst_ptr = self.as_mut_ptr().wrapping_add(filled_pos as usize);
-                self.filled_pos = new_filled_pos as LenUint;
-                unsafe { unsafe_wild_copy!([T; LEN], src_ptr, dst_ptr, LEN); }
+                let dst_ptr = self.as_mut_ptr().wrapping_add(filled_pos);
+                self.filled_pos = L::from_usize(new_filled_pos);
+                unsafe {
+                    unsafe_wild_copy!([T; LEN], src_ptr, dst_ptr, LEN);
+                    self.chunk.set_init(new_filled_pos);
+                }
                 Ok(())
             } else {
                 Err(WriteBufferError::BufferFull)
@@ -214,124 +254,179 @@ declare_const_impl! {
 
         #[inline(always)]
         fn write(&mut self, data: &[T]) {
-            let filled_pos = self.filled_pos as usize;
+            let filled_pos = self.filled_pos.to_usize();
             let new_filled_pos_len = filled_pos + data.len();
-            self.filled_pos = new_filled_pos_len as LenUint;
-            #[cfg(not(feature = "const-trait"))]
-            self.chunk.as_mut_slice()[filled_pos..new_filled_pos_len].copy_from_slice(data);
-            #[cfg(feature = "const-trait")]
+            assert!(new_filled_pos_len <= N, "buffer overflow");
+            self.filled_pos = L::from_usize(new_filled_pos_len);
             unsafe {
-
-                (&mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(filled_pos), data.len())).copy_from_slice(data);
+                data.as_ptr().copy_to_nonoverlapping(self.chunk.as_mut_ptr().wrapping_add(filled_pos), data.len());
+                self.chunk.set_init(new_filled_pos_len);
             }
         }
 
         #[inline(always)]
         fn remaining_space(&self) -> usize {
-            N - self.filled_pos as usize
+            N - self.filled_pos.to_usize()
         }
 
         #[inline(always)]
         fn filled_pos(&self) -> usize {
-            self.filled_pos as usize
+            self.filled_pos.to_usize()
         }
 
         #[inline(always)]
         unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
-            self.filled_pos = filled_pos as LenUint;
+            self.filled_pos = L::from_usize(filled_pos);
         }
 
         #[inline(always)]
         fn capacity(&self) -> usize {
             N
         }
+
+        #[inline(always)]
+        fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+            let filled_pos = self.filled_pos.to_usize();
+            unsafe {
+                &mut *slice_from_raw_parts_mut(
+                    self.chunk.as_mut_ptr().wrapping_add(filled_pos) as *mut MaybeUninit<T>,
+                    N - filled_pos,
+                )
+            }
+        }
+
+        #[inline(always)]
+        unsafe fn commit(&mut self, len: usize) {
+            let filled_pos = self.filled_pos.to_usize() + len;
+            debug_assert!(filled_pos <= N);
+            self.filled_pos = L::from_usize(filled_pos);
+            unsafe { self.chunk.set_init(filled_pos) };
+        }
     }
 }
 
 declare_const_impl! {
-    (impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>> ReadBuf<T> for Buffer<[T; N], A, C>),
-    (impl<T, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>> const ReadBuf<T> for Buffer<[T; N], A, C>) {
+    (impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> ReadBuf<T> for Buffer<[T; N], A, C, L>),
+    (impl<T, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const ReadBuf<T> for Buffer<[T; N], A, C, L>) {
         #[inline(always)]
         fn read(&mut self, len: usize) -> &[T] {
-            let pos = self.pos as usize;
-            let slice_len = const_min!(len, self.filled_pos as usize - pos);
+            let pos = self.pos.to_usize();
+            let slice_len = const_min!(len, self.filled_pos.to_usize() - pos);
             let new_pos = pos + slice_len;
-            self.pos = new_pos as LenUint;
+            self.pos = L::from_usize(new_pos);
             unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(pos), slice_len) }
         }
 
         #[inline(always)]
         unsafe fn get_continuous(&self, len: usize) -> &[T] {
-            let pos = self.pos as usize;
-            let filled_pos = self.filled_pos as usize;
+            let pos = self.pos.to_usize();
+            let filled_pos = self.filled_pos.to_usize();
             let slice_len = const_min!(len, filled_pos - pos);
             unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(pos), slice_len) }
         }
 
-        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
-            let pos = self.pos as usize;
-            let filled_pos = self.filled_pos as usize;
-            let slice_len = const_min!(len, filled_pos - pos);
-            unsafe { &mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(pos), slice_len) }
-        }
-
         #[inline(always)]
         fn remaining(&self) -> usize {
-            (self.filled_pos - self.pos) as usize
+            self.filled_pos.to_usize() - self.pos.to_usize()
         }
 
         #[inline(always)]
         fn advance(&mut self, len: usize) {
-            let pos = self.pos as usize;
-            if cfg!(feature = "const-trait") {
-                let filled_pos = self.filled_pos;
-                let new_pos = (pos + len) as LenUint;
-                self.pos = if filled_pos > new_pos {
-                    new_pos
-                } else {
-                    filled_pos
-                };
-            } else {
-                self.pos = const_min!(self.filled_pos, (pos + len) as LenUint);
-            }
+            let new_pos = self.pos.to_usize() + len;
+            self.pos = L::from_usize(const_min!(self.filled_pos.to_usize(), new_pos));
         }
 
         #[inline(always)]
         fn pos(&self) -> usize {
-            self.pos as usize
+            self.pos.to_usize()
         }
 
         #[inline(always)]
         unsafe fn set_pos(&mut self, pos: usize) {
-            self.pos = pos as LenUint;
+            self.pos = L::from_usize(pos);
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> ReadBufMut<T> for Buffer<[T; N], A, C, L>),
+    (impl<T, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const ReadBufMut<T> for Buffer<[T; N], A, C, L>) {
+        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+            let pos = self.pos.to_usize();
+            let filled_pos = self.filled_pos.to_usize();
+            let slice_len = const_min!(len, filled_pos - pos);
+            unsafe { &mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(pos), slice_len) }
         }
     }
 }
 
 #[cfg(feature = "std")]
 impl<S: std::io::Read> ReadToBuf<u8> for S {
+    /// Reads through [`std::io::Read::read_buf`], so readers such as sockets fill the spare capacity
+    /// without zeroing it first. Retries on [`std::io::ErrorKind::Interrupted`].
     #[inline(always)]
-    fn read_to_buf(&mut self, buf: &mut impl Buf<u8>) -> Result<(), ()> {
-        let filled_pos = buf.filled_pos() as usize;
-        let slice = unsafe {
-            &mut *slice_from_raw_parts_mut(
-                Chunk::as_mut_ptr(buf).wrapping_add(filled_pos),
-                buf.capacity() - filled_pos,
-            )
+    fn read_to_buf(&mut self, buf: &mut impl Buf<u8>) -> Result<usize, ReadToBufError> {
+        let mut spare = BorrowedBuf::from(buf.spare_capacity_mut());
+        if spare.capacity() == 0 {
+            return Err(ReadToBufError::BufferFull);
+        }
+        let read_length = loop {
+            match self.read_buf(spare.unfilled()) {
+                Ok(()) if spare.len() == 0 => return Err(ReadToBufError::Eof),
+                Ok(()) => break spare.len(),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    return Err(ReadToBufError::WouldBlock)
+                }
+                Err(err) => return Err(ReadToBufError::Io(err)),
+            }
         };
-        let read_length = self.read(slice).map_err(|_| ())?;
-        if read_length == 0 {
-            Err(())?
-        }
-        unsafe { buf.set_filled_pos(filled_pos + read_length) };
-        Ok(())
+        unsafe { buf.commit(read_length) };
+        Ok(read_length)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: std::io::Write> WriteFromBuf<u8> for S {
+    /// Retries on [`std::io::ErrorKind::Interrupted`].
+    #[inline(always)]
+    fn write_from_buf(&mut self, buf: &mut impl ReadBuf<u8>) -> Result<usize, WriteFromBufError> {
+        if buf.remaining() == 0 {
+            return Ok(0);
+        }
+        let data = unsafe { buf.get_continuous(buf.remaining()) };
+        let write_length = loop {
+            match self.write(data) {
+                Ok(0) => return Err(WriteFromBufError::WriteZero),
+                Ok(write_length) => break write_length,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    return Err(WriteFromBufError::WouldBlock)
+                }
+                Err(err) => return Err(WriteFromBufError::Io(err)),
+            }
+        };
+        buf.advance(write_length);
+        Ok(write_length)
+    }
+
+    #[inline(always)]
+    fn write_all_from_buf(
+        &mut self,
+        buf: &mut impl ReadBuf<u8>,
+    ) -> Result<usize, WriteFromBufError> {
+        let mut written = 0;
+        while buf.remaining() != 0 {
+            written += self.write_from_buf(buf)?;
+        }
+        Ok(written)
     }
 }
 
 #[cfg(feature = "std")]
 declare_const_impl! {
-    (impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>> std::io::Write for Buffer<[u8; N], A, C>),
-    (impl<const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<u8>> std::io::Write for Buffer<[u8; N], A, C>) {
+    (impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> std::io::Write for Buffer<[u8; N], A, C, L>),
+    (impl<const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<u8>, L: const BufIndex> std::io::Write for Buffer<[u8; N], A, C, L>) {
         #[inline(always)]
         fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
             let backup_filled_pos = self.filled_pos();
@@ -347,9 +442,66 @@ declare_const_impl! {
     }
 }
 
+#[cfg(feature = "std")]
 declare_const_impl! {
-    (impl<T: Copy + Debug, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>> Debug for Buffer<[T; N], A, C>),
-    (impl<T: Copy + Debug, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>> Debug for Buffer<[T; N], A, C>) {
+    (impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> std::io::Read for Buffer<[u8; N], A, C, L>),
+    (impl<const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<u8>, L: const BufIndex> std::io::Read for Buffer<[u8; N], A, C, L>) {
+        #[inline(always)]
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let data = ReadBuf::read(self, buf.len());
+            let read_length = data.len();
+            buf[..read_length].copy_from_slice(data);
+            Ok(read_length)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+declare_const_impl! {
+    (impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> std::io::BufRead for Buffer<[u8; N], A, C, L>),
+    (impl<const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<u8>, L: const BufIndex> std::io::BufRead for Buffer<[u8; N], A, C, L>) {
+        #[inline(always)]
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            let remaining = self.remaining();
+            Ok(unsafe { (*self).get_continuous(remaining) })
+        }
+
+        #[inline(always)]
+        fn consume(&mut self, amt: usize) {
+            self.advance(amt)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+declare_const_impl! {
+    (impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> std::io::Seek for Buffer<[u8; N], A, C, L>),
+    (impl<const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<u8>, L: const BufIndex> std::io::Seek for Buffer<[u8; N], A, C, L>) {
+        /// Moves `pos`, which can't go past `filled_pos`.
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            // Stay in `u64` until the bounds check, `isize` can't hold every offset on 32-bit.
+            let new_pos = match pos {
+                std::io::SeekFrom::Start(offset) => Some(offset),
+                std::io::SeekFrom::End(offset) => (self.filled_pos() as u64).checked_add_signed(offset),
+                std::io::SeekFrom::Current(offset) => (self.pos() as u64).checked_add_signed(offset),
+            };
+            match new_pos {
+                Some(new_pos) if new_pos <= self.filled_pos() as u64 => {
+                    unsafe { self.set_pos(new_pos as usize) };
+                    Ok(new_pos)
+                }
+                _ => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "seek position out of filled range",
+                )),
+            }
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T: Copy + Debug, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Debug for Buffer<[T; N], A, C, L>),
+    (impl<T: Copy + Debug, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> Debug for Buffer<[T; N], A, C, L>) {
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             self.chunk.as_slice()[self.pos()..self.filled_pos()].fmt(f)
         }
@@ -491,6 +643,25 @@ mod tests {
         test!(BoxedBuffer::<[u8; 16]>::new());
     }
 
+    #[test]
+    fn test_compact() {
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+            buffer.write(b"hello world");
+            buffer.advance(6);
+            buffer.compact();
+            assert_eq!(buffer.pos(), 0);
+            assert_eq!(buffer.filled_pos(), 5);
+            assert_eq!(buffer.remaining_space(), 11);
+            assert_eq!(buffer.read(5), b"world");
+        }}
+
+        test!(Buffer::<[u8; 16]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedBuffer::<[u8; 16]>::new());
+    }
+
     #[test]
     fn test_advance() {
         macro_rules! test {
@@ -528,6 +699,125 @@ mod tests {
         test!(BoxedBuffer::<[u8; 16]>::new());
     }
 
+    #[test]
+    fn test_read_to_buf() {
+        struct FlakyReader(Vec<std::io::Result<&'static [u8]>>);
+
+        impl std::io::Read for FlakyReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let data = self.0.remove(0)?;
+                buf[..data.len()].copy_from_slice(data);
+                Ok(data.len())
+            }
+        }
+
+        let mut buffer: Buffer<[u8; 8]> = Buffer::new();
+        let mut reader = FlakyReader(vec![
+            Err(std::io::ErrorKind::Interrupted.into()),
+            Ok(b"hello"),
+            Err(std::io::ErrorKind::WouldBlock.into()),
+            Err(std::io::ErrorKind::ConnectionReset.into()),
+            Ok(b"abc"),
+            Ok(b""),
+        ]);
+        assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 5);
+        assert!(matches!(
+            reader.read_to_buf(&mut buffer),
+            Err(ReadToBufError::WouldBlock)
+        ));
+        assert!(matches!(
+            reader.read_to_buf(&mut buffer),
+            Err(ReadToBufError::Io(err)) if err.kind() == std::io::ErrorKind::ConnectionReset
+        ));
+        assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 3);
+        assert!(matches!(
+            reader.read_to_buf(&mut buffer),
+            Err(ReadToBufError::BufferFull)
+        ));
+        buffer.clear();
+        assert!(matches!(
+            reader.read_to_buf(&mut buffer),
+            Err(ReadToBufError::Eof)
+        ));
+    }
+
+    #[test]
+    fn test_write_from_buf() {
+        struct ChokedWriter {
+            data: Vec<u8>,
+            limit: usize,
+        }
+
+        impl std::io::Write for ChokedWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.limit == 0 {
+                    return Err(std::io::ErrorKind::WouldBlock.into());
+                }
+                let write_length = buf.len().min(3).min(self.limit);
+                self.limit -= write_length;
+                self.data.extend_from_slice(&buf[..write_length]);
+                Ok(write_length)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(b"hello world");
+        let mut writer = ChokedWriter {
+            data: Vec::new(),
+            limit: 8,
+        };
+        assert_eq!(writer.write_from_buf(&mut buffer).unwrap(), 3);
+        assert_eq!(buffer.remaining(), 8);
+        assert!(matches!(
+            writer.write_all_from_buf(&mut buffer),
+            Err(WriteFromBufError::WouldBlock)
+        ));
+        assert_eq!(buffer.remaining(), 3);
+
+        writer.limit = usize::MAX;
+        assert_eq!(writer.write_all_from_buf(&mut buffer).unwrap(), 3);
+        assert_eq!(writer.data, b"hello world");
+        assert_eq!(writer.write_from_buf(&mut buffer).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_io_read() {
+        use std::io::{BufRead, Read};
+
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(b"hello\nworld");
+        let mut line = String::new();
+        assert_eq!(BufRead::read_line(&mut buffer, &mut line).unwrap(), 6);
+        assert_eq!(line, "hello\n");
+
+        let mut data = [0; 8];
+        assert_eq!(Read::read(&mut buffer, &mut data).unwrap(), 5);
+        assert_eq!(&data[..5], b"world");
+        assert_eq!(Read::read(&mut buffer, &mut data).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_io_seek() {
+        use std::io::{Seek, SeekFrom};
+
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(b"hello world");
+        assert_eq!(buffer.seek(SeekFrom::Start(6)).unwrap(), 6);
+        assert_eq!(buffer.read(5), b"world");
+        assert_eq!(buffer.seek(SeekFrom::End(-5)).unwrap(), 6);
+        assert_eq!(buffer.seek(SeekFrom::Current(-6)).unwrap(), 0);
+        assert!(buffer.seek(SeekFrom::Current(-1)).is_err());
+        assert!(buffer.seek(SeekFrom::End(1)).is_err());
+        assert!(buffer.seek(SeekFrom::Current(1 << 32 | 1)).is_err());
+        assert!(buffer.seek(SeekFrom::Start(u64::MAX)).is_err());
+        assert!(buffer.seek(SeekFrom::End(i64::MIN)).is_err());
+        assert_eq!(buffer.pos(), 0);
+    }
+
     #[cfg(feature = "const-trait")]
     #[test]
     fn test_clone() {
@@ -0,0 +1,131 @@
+use std::alloc::Allocator;
+
+use bytes::{buf::UninitSlice, Bytes, BytesMut};
+
+use crate::{BufIndex, Buffer, Chunk, ChunkBuilder, ReadBuf, WriteBuf, WriteBufferError};
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> bytes::Buf
+    for Buffer<[u8; N], A, C, L>
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        ReadBuf::remaining(self)
+    }
+
+    #[inline(always)]
+    fn chunk(&self) -> &[u8] {
+        unsafe { self.get_continuous(ReadBuf::remaining(self)) }
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, cnt: usize) {
+        assert!(cnt <= ReadBuf::remaining(self), "cannot advance past `remaining`");
+        ReadBuf::advance(self, cnt)
+    }
+}
+
+unsafe impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> bytes::BufMut
+    for Buffer<[u8; N], A, C, L>
+{
+    #[inline(always)]
+    fn remaining_mut(&self) -> usize {
+        self.remaining_space()
+    }
+
+    #[inline(always)]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining_space(),
+            "cannot advance past `remaining_mut`"
+        );
+        self.commit(cnt)
+    }
+
+    #[inline(always)]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        UninitSlice::uninit(self.spare_capacity_mut())
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex>
+    From<Buffer<[u8; N], A, C, L>> for Bytes
+{
+    /// Copies the unread region into a new [`Bytes`].
+    #[inline(always)]
+    fn from(buffer: Buffer<[u8; N], A, C, L>) -> Self {
+        Bytes::copy_from_slice(unsafe { buffer.get_continuous(buffer.remaining()) })
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex>
+    From<Buffer<[u8; N], A, C, L>> for BytesMut
+{
+    /// Copies the unread region into a new [`BytesMut`].
+    #[inline(always)]
+    fn from(buffer: Buffer<[u8; N], A, C, L>) -> Self {
+        BytesMut::from(unsafe { buffer.get_continuous(buffer.remaining()) })
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> TryFrom<Bytes>
+    for Buffer<[u8; N], A, C, L>
+{
+    type Error = WriteBufferError;
+
+    /// Copies `bytes` into a new buffer, failing when it is longer than `N`.
+    #[inline(always)]
+    fn try_from(bytes: Bytes) -> Result<Self, Self::Error> {
+        let mut buffer = Self::new();
+        buffer.try_write(&bytes)?;
+        Ok(buffer)
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> TryFrom<BytesMut>
+    for Buffer<[u8; N], A, C, L>
+{
+    type Error = WriteBufferError;
+
+    /// Copies `bytes` into a new buffer, failing when it is longer than `N`.
+    #[inline(always)]
+    fn try_from(bytes: BytesMut) -> Result<Self, Self::Error> {
+        let mut buffer = Self::new();
+        buffer.try_write(&bytes)?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use bytes::BufMut as _;
+
+    use super::*;
+
+    #[test]
+    fn test_bytes_buf() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(b"\x00\x2Ahello");
+        assert_eq!(bytes::Buf::get_u16(&mut buffer), 42);
+        assert_eq!(bytes::Buf::chunk(&buffer), b"hello");
+        assert_eq!(bytes::Buf::copy_to_bytes(&mut buffer, 5), Bytes::from_static(b"hello"));
+        assert!(!bytes::Buf::has_remaining(&buffer));
+    }
+
+    #[test]
+    fn test_bytes_buf_mut() {
+        let mut buffer: Buffer<[u8; 8]> = Buffer::new();
+        buffer.put_u32(0xDEADBEEF);
+        buffer.put_slice(b"abc");
+        assert_eq!(buffer.remaining_mut(), 1);
+        assert_eq!(buffer.read(7), b"\xDE\xAD\xBE\xEFabc");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let buffer = Buffer::<[u8; 8]>::try_from(Bytes::from_static(b"hello")).unwrap();
+        assert_eq!(Bytes::from(buffer.clone()), Bytes::from_static(b"hello"));
+        assert_eq!(&BytesMut::from(buffer)[..], b"hello");
+        assert!(Buffer::<[u8; 4]>::try_from(BytesMut::from(&b"hello"[..])).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use core::{
+    mem::MaybeUninit,
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
+};
+
+use crate::{const_min, Chunk, ReadBuf, ReadBufMut};
+
+/// Ordered list of [`ReadBuf`] segments read as one continuous stream.
+///
+/// [`ReadBuf::read`] hands out slices of a segment when it can and gathers reads that
+/// straddle two segments into a scratch buffer of `SCRATCH` elements.
+/// [`ReadBuf::pos`] counts the elements read through the chain since the segments were pushed,
+/// reads through [`BufChain::segments_mut`] don't count.
+pub struct BufChain<B, T = u8, const SCRATCH: usize = 64> {
+    bufs: Vec<B>,
+    origins: Vec<usize>,
+    index: usize,
+    pos: usize,
+    scratch: [MaybeUninit<T>; SCRATCH],
+}
+
+impl<B: ReadBuf<T>, T: Copy, const SCRATCH: usize> BufChain<B, T, SCRATCH> {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            bufs: Vec::new(),
+            origins: Vec::new(),
+            index: 0,
+            pos: 0,
+            scratch: [const { MaybeUninit::uninit() }; SCRATCH],
+        }
+    }
+
+    /// Appends `buf` after the last segment.
+    #[inline(always)]
+    pub fn push(&mut self, buf: B) {
+        self.origins.push(buf.pos());
+        self.bufs.push(buf);
+    }
+
+    /// Segments that still have data to read, e.g. to pass them to a vectored write.
+    #[inline(always)]
+    pub fn segments(&self) -> &[B] {
+        &self.bufs[self.first_unread()..]
+    }
+
+    /// Mutable version of [`BufChain::segments`], reading through it moves the chain as well
+    /// but isn't counted in [`ReadBuf::pos`].
+    #[inline(always)]
+    pub fn segments_mut(&mut self) -> &mut [B] {
+        self.skip_drained();
+        &mut self.bufs[self.index..]
+    }
+
+    #[inline(always)]
+    pub fn into_segments(self) -> Vec<B> {
+        self.bufs
+    }
+
+    #[inline(always)]
+    fn first_unread(&self) -> usize {
+        let mut index = self.index;
+        while index < self.bufs.len() && self.bufs[index].remaining() == 0 {
+            index += 1;
+        }
+        index
+    }
+
+    #[inline(always)]
+    fn skip_drained(&mut self) {
+        self.index = self.first_unread();
+    }
+}
+
+impl<B: ReadBuf<T>, T: Copy, const SCRATCH: usize> Default for BufChain<B, T, SCRATCH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: ReadBuf<T>, T: Copy, const SCRATCH: usize> FromIterator<B> for BufChain<B, T, SCRATCH> {
+    fn from_iter<I: IntoIterator<Item = B>>(iter: I) -> Self {
+        let mut chain = Self::new();
+        for buf in iter {
+            chain.push(buf);
+        }
+        chain
+    }
+}
+
+impl<B: ReadBufMut<T>, T: Copy, const SCRATCH: usize> Chunk<T> for BufChain<B, T, SCRATCH> {
+    /// Continuous readable region of the current segment.
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        unsafe { self.get_continuous(usize::MAX) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { self.get_continuous_mut(usize::MAX) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.as_slice().as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.as_mut_slice().as_mut_ptr()
+    }
+}
+
+impl<B: ReadBuf<T>, T: Copy, const SCRATCH: usize> ReadBuf<T> for BufChain<B, T, SCRATCH> {
+    /// Returns at most `SCRATCH` elements when the read straddles two segments.
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[T] {
+        self.skip_drained();
+        let Some(buf) = self.bufs.get_mut(self.index) else {
+            return &[];
+        };
+        let continuous_len = unsafe { buf.get_continuous(len) }.len();
+        if continuous_len == len || continuous_len >= SCRATCH {
+            let slice = self.bufs[self.index].read(len);
+            self.pos += slice.len();
+            return slice;
+        }
+        let scratch_len = const_min!(len, SCRATCH);
+        let scratch = self.scratch.as_mut_ptr() as *mut T;
+        let mut read_len = 0;
+        for buf in &mut self.bufs[self.index..] {
+            while read_len < scratch_len {
+                let slice = buf.read(scratch_len - read_len);
+                if slice.is_empty() {
+                    break;
+                }
+                unsafe {
+                    slice
+                        .as_ptr()
+                        .copy_to_nonoverlapping(scratch.add(read_len), slice.len())
+                };
+                read_len += slice.len();
+            }
+            if read_len == scratch_len {
+                break;
+            }
+        }
+        self.pos += read_len;
+        unsafe { &*slice_from_raw_parts(scratch, read_len) }
+    }
+
+    /// Only looks at the current segment, use [`ReadBuf::read`] to cross into the next one.
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[T] {
+        match self.bufs.get(self.first_unread()) {
+            Some(buf) => unsafe { buf.get_continuous(len) },
+            None => &[],
+        }
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.bufs[self.index..]
+            .iter()
+            .map(|buf| buf.remaining())
+            .sum()
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, mut len: usize) {
+        for buf in &mut self.bufs[self.index..] {
+            if len == 0 {
+                break;
+            }
+            let advance_len = const_min!(len, buf.remaining());
+            buf.advance(advance_len);
+            self.pos += advance_len;
+            len -= advance_len;
+        }
+        self.skip_drained();
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Rewinds or moves every segment so that `pos` elements of the chain are read.
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        let mut left = pos;
+        for (buf, &origin) in self.bufs.iter_mut().zip(&self.origins) {
+            unsafe { buf.set_pos(origin) };
+            let advance_len = const_min!(left, buf.remaining());
+            buf.advance(advance_len);
+            left -= advance_len;
+        }
+        self.pos = pos - left;
+        self.index = 0;
+        self.skip_drained();
+    }
+}
+
+impl<B: ReadBufMut<T>, T: Copy, const SCRATCH: usize> ReadBufMut<T> for BufChain<B, T, SCRATCH> {
+    /// Only looks at the current segment, use [`ReadBuf::read`] to cross into the next one.
+    #[inline(always)]
+    unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+        self.skip_drained();
+        match self.bufs.get_mut(self.index) {
+            Some(buf) => unsafe { buf.get_continuous_mut(len) },
+            None => unsafe {
+                &mut *slice_from_raw_parts_mut(self.scratch.as_mut_ptr() as *mut T, 0)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{Buffer, ReadBufCheckpoint, ReadBufExt, WriteBuf, WriteVectoredFromBuf};
+
+    fn chain(segments: &[&[u8]]) -> BufChain<Buffer<[u8; 4]>, u8, 4> {
+        segments
+            .iter()
+            .map(|segment| {
+                let mut buffer = Buffer::new();
+                buffer.write(segment);
+                buffer
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_across_segments() {
+        let mut chain = chain(&[b"ab", b"", b"cdef", b"g"]);
+        assert_eq!(chain.remaining(), 7);
+        assert_eq!(chain.read(1), b"a");
+        assert_eq!(chain.read(3), b"bcd");
+        assert_eq!(chain.pos(), 4);
+        assert_eq!(chain.read(8), b"efg");
+        assert_eq!(chain.read(8), b"");
+        assert_eq!(chain.pos(), 7);
+
+        unsafe { chain.set_pos(1) };
+        assert_eq!(chain.read_u32_be(), u32::from_be_bytes(*b"bcde"));
+        chain.advance(1);
+        assert_eq!(chain.read(8), b"g");
+    }
+
+    #[test]
+    fn test_checkpoint_rewinds_segments() {
+        let mut chain = chain(&[b"abcd", b"efgh"]);
+        {
+            let mut checkpoint = chain.read_checkpoint();
+            checkpoint.advance(6);
+        }
+        assert_eq!(chain.read(4), b"abcd");
+        assert_eq!(chain.segments().len(), 1);
+    }
+
+    #[test]
+    fn test_write_into_segment() {
+        let mut chain = chain(&[b"ab", b"cd"]);
+        assert_eq!(chain.read(1), b"a");
+        chain.segments_mut()[0].write(b"xy");
+        assert_eq!(chain.pos(), 1);
+        assert_eq!(chain.read(8), b"bxyc");
+        assert_eq!(chain.pos(), 5);
+
+        unsafe { chain.set_pos(3) };
+        assert_eq!(chain.pos(), 3);
+        assert_eq!(chain.read(8), b"ycd");
+    }
+
+    #[test]
+    fn test_vectored_segments() {
+        let mut chain = chain(&[b"head", b"body"]);
+        chain.advance(2);
+        let mut sink = Vec::new();
+        assert_eq!(
+            sink.write_vectored_from_bufs(chain.segments_mut()).unwrap(),
+            6
+        );
+        assert_eq!(sink, b"adbody");
+        assert_eq!(chain.remaining(), 0);
+    }
+}
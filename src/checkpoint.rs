@@ -0,0 +1,215 @@
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{ReadBuf, WriteBuf};
+
+/// Snapshot of `pos` that is restored on drop unless [`ReadCheckpoint::commit`] was called.
+pub struct ReadCheckpoint<'a, T, B: ReadBuf<T> + ?Sized> {
+    buf: &'a mut B,
+    pos: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, B: ReadBuf<T> + ?Sized> ReadCheckpoint<'a, T, B> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut B) -> Self {
+        Self {
+            pos: buf.pos(),
+            buf,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Keeps everything read since the checkpoint was taken.
+    #[inline(always)]
+    pub fn commit(self) {
+        core::mem::forget(self)
+    }
+
+    /// Restores `pos` right away, same as dropping the checkpoint.
+    #[inline(always)]
+    pub fn rollback(self) {}
+}
+
+impl<T, B: ReadBuf<T> + ?Sized> Drop for ReadCheckpoint<'_, T, B> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe { self.buf.set_pos(self.pos) }
+    }
+}
+
+impl<T, B: ReadBuf<T> + ?Sized> Deref for ReadCheckpoint<'_, T, B> {
+    type Target = B;
+
+    #[inline(always)]
+    fn deref(&self) -> &B {
+        self.buf
+    }
+}
+
+impl<T, B: ReadBuf<T> + ?Sized> DerefMut for ReadCheckpoint<'_, T, B> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut B {
+        self.buf
+    }
+}
+
+/// Snapshot of `filled_pos` that is restored on drop unless [`WriteCheckpoint::commit`] was called.
+///
+/// The buffer must not be cleared or compacted while the checkpoint is alive,
+/// so take it on the inner buffer of an [`AutoCompact`](crate::AutoCompact).
+pub struct WriteCheckpoint<'a, T, B: WriteBuf<T> + ?Sized> {
+    buf: &'a mut B,
+    filled_pos: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, B: WriteBuf<T> + ?Sized> WriteCheckpoint<'a, T, B> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut B) -> Self {
+        Self {
+            filled_pos: buf.filled_pos(),
+            buf,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Keeps everything written since the checkpoint was taken.
+    #[inline(always)]
+    pub fn commit(self) {
+        core::mem::forget(self)
+    }
+
+    /// Restores `filled_pos` right away, same as dropping the checkpoint.
+    #[inline(always)]
+    pub fn rollback(self) {}
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> Drop for WriteCheckpoint<'_, T, B> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe { self.buf.set_filled_pos(self.filled_pos) }
+    }
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> Deref for WriteCheckpoint<'_, T, B> {
+    type Target = B;
+
+    #[inline(always)]
+    fn deref(&self) -> &B {
+        self.buf
+    }
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> DerefMut for WriteCheckpoint<'_, T, B> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut B {
+        self.buf
+    }
+}
+
+pub trait ReadBufCheckpoint<T>: ReadBuf<T> {
+    #[inline(always)]
+    fn read_checkpoint(&mut self) -> ReadCheckpoint<'_, T, Self> {
+        ReadCheckpoint::new(self)
+    }
+
+    /// Runs `f` and rolls `pos` back when it returns an error.
+    #[inline(always)]
+    fn read_transaction<R, E>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<R, E>,
+    ) -> Result<R, E> {
+        let mut checkpoint = self.read_checkpoint();
+        let result = f(&mut checkpoint)?;
+        checkpoint.commit();
+        Ok(result)
+    }
+}
+
+impl<T, B: ReadBuf<T> + ?Sized> ReadBufCheckpoint<T> for B {}
+
+pub trait WriteBufCheckpoint<T>: WriteBuf<T> {
+    #[inline(always)]
+    fn write_checkpoint(&mut self) -> WriteCheckpoint<'_, T, Self> {
+        WriteCheckpoint::new(self)
+    }
+
+    /// Runs `f` and rolls `filled_pos` back when it returns an error.
+    #[inline(always)]
+    fn write_transaction<R, E>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<R, E>,
+    ) -> Result<R, E> {
+        let mut checkpoint = self.write_checkpoint();
+        let result = f(&mut checkpoint)?;
+        checkpoint.commit();
+        Ok(result)
+    }
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> WriteBufCheckpoint<T> for B {}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::{Buffer, ReadBufExt, ReadBufferError, RingBuffer, WriteBufExt, WriteBufferError};
+
+    use super::*;
+
+    fn decode_frame(buf: &mut impl ReadBuf<u8>) -> Result<u32, ReadBufferError> {
+        buf.read_transaction(|buf| {
+            let len = buf.try_read_u8()?;
+            let value = buf.try_read_u32_be()?;
+            if len != 4 {
+                return Err(ReadBufferError::BufferUnderflow);
+            }
+            Ok(value)
+        })
+    }
+
+    #[test]
+    fn test_read_rollback() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(&[4, 0, 0]);
+        assert!(decode_frame(&mut &mut buffer).is_err());
+        assert_eq!(buffer.pos(), 0);
+
+        buffer.write(&[1, 2]);
+        assert_eq!(decode_frame(&mut buffer), Ok(0x102));
+        assert_eq!(buffer.remaining(), 0);
+
+        buffer.write(b"abc");
+        {
+            let mut checkpoint = buffer.read_checkpoint();
+            assert_eq!(checkpoint.read(3), b"abc");
+        }
+        assert_eq!(buffer.remaining(), 3);
+        let mut checkpoint = buffer.read_checkpoint();
+        checkpoint.advance(1);
+        checkpoint.commit();
+        assert_eq!(buffer.read(2), b"bc");
+    }
+
+    #[test]
+    fn test_write_rollback() {
+        let mut buffer: RingBuffer<[u8; 8]> = RingBuffer::new();
+        buffer.write(&[0; 6]);
+        buffer.advance(6);
+        buffer.write(&[1, 2]);
+
+        let result = buffer.write_transaction(|buf| {
+            buf.try_write_u32_be(0xDEADBEEF)?;
+            buf.try_write_u32_be(0xDEADBEEF)
+        });
+        assert_eq!(result, Err(WriteBufferError::BufferFull));
+        assert_eq!(buffer.as_slices(), (&[1, 2][..], &[][..]));
+
+        let mut checkpoint = buffer.write_checkpoint();
+        checkpoint.write_u16_be(0x0304);
+        checkpoint.commit();
+        assert_eq!(buffer.read_u32_be(), 0x01020304);
+    }
+}
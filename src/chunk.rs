@@ -1,4 +1,8 @@
-use core::{alloc::Allocator, mem::MaybeUninit};
+use core::{
+    alloc::Allocator,
+    mem::MaybeUninit,
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
+};
 
 use crate::{declare_const_impl, Chunk, ChunkBuilder};
 
@@ -11,9 +15,10 @@ declare_const_impl! {
             <[T; N] as ChunkBuilder<A>>::new()
         }
 
+        /// Zeroed, [`UninitChunk`] skips initializing the chunk.
         #[inline(always)]
         fn new() -> Self {
-            unsafe { MaybeUninit::uninit().assume_init() }
+            <[T; N] as ChunkBuilder<A>>::new_zeroed()
         }
 
         #[inline(always)]
@@ -54,7 +59,7 @@ declare_const_impl! {
     (impl<T: Copy + Clone, const N: usize, A: Allocator + Copy + Clone> const ChunkBuilder<A> for Box<[T; N], A>) {
         #[inline(always)]
         default fn new_in(alloc: A) -> Self {
-            unsafe { Box::new_uninit_in(alloc).assume_init() }
+            unsafe { Box::new_zeroed_in(alloc).assume_init() }
         }
 
         #[inline(always)]
@@ -106,7 +111,7 @@ declare_const_impl! {
 
         #[inline(always)]
         default fn new() -> Self {
-            unsafe { Box::new_uninit().assume_init() }
+            unsafe { Box::new_zeroed().assume_init() }
         }
 
         #[inline(always)]
@@ -141,3 +146,143 @@ declare_const_impl! {
         }
     }
 }
+
+/// Chunk of `N` possibly uninitialized elements, tracking its initialized prefix like `BorrowedBuf`.
+///
+/// [`Chunk::as_slice`] only covers the initialized prefix, which the buffers extend as they are
+/// written to, see [`UninitBuffer`](crate::UninitBuffer).
+pub struct UninitChunk<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    init: usize,
+}
+
+impl<T, const N: usize> UninitChunk<T, N> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            init: 0,
+        }
+    }
+
+    /// Length of the initialized prefix.
+    #[inline(always)]
+    pub const fn init_len(&self) -> usize {
+        self.init
+    }
+}
+
+impl<T, const N: usize> Default for UninitChunk<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+declare_const_impl! {
+    (impl<T, const N: usize, A: Allocator> ChunkBuilder<A> for UninitChunk<T, N>),
+    (impl<T, const N: usize, A: Allocator> const ChunkBuilder<A> for UninitChunk<T, N>) {
+        #[inline(always)]
+        fn new_in(alloc: A) -> Self {
+            core::mem::forget(alloc);
+            UninitChunk::new()
+        }
+
+        #[inline(always)]
+        fn new() -> Self {
+            UninitChunk::new()
+        }
+
+        #[inline(always)]
+        fn new_zeroed() -> Self {
+            Self {
+                data: unsafe { MaybeUninit::zeroed().assume_init() },
+                init: 0,
+            }
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, const N: usize> Chunk<T> for UninitChunk<T, N>),
+    (impl<T, const N: usize> const Chunk<T> for UninitChunk<T, N>) {
+        #[inline(always)]
+        fn as_slice(&self) -> &[T] {
+            unsafe { &*slice_from_raw_parts(self.data.as_ptr() as *const T, self.init) }
+        }
+
+        #[inline(always)]
+        fn as_mut_slice(&mut self) -> &mut [T] {
+            unsafe { &mut *slice_from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.init) }
+        }
+
+        #[inline(always)]
+        fn as_ptr(&self) -> *const T {
+            self.data.as_ptr() as *const T
+        }
+
+        #[inline(always)]
+        fn as_mut_ptr(&mut self) -> *mut T {
+            self.data.as_mut_ptr() as *mut T
+        }
+
+        #[inline(always)]
+        unsafe fn set_init(&mut self, len: usize) {
+            debug_assert!(len <= N);
+            if len > self.init {
+                self.init = len;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{Buf, ReadBuf, ReadToBuf, RingBuffer, UninitBuffer, WriteBuf};
+
+    #[test]
+    fn test_init_tracking() {
+        let mut buffer: UninitBuffer<u8, 16> = UninitBuffer::new();
+        assert_eq!(buffer.as_slice(), &[]);
+        buffer.write(b"hello");
+        buffer.try_write_fast(b"!!").unwrap();
+        assert_eq!(buffer.as_slice(), b"hello!!");
+        buffer.clear();
+        buffer.write(b"hi");
+        assert_eq!(buffer.chunk.init_len(), 7);
+        assert_eq!(format!("{buffer:?}"), "[104, 105]");
+    }
+
+    #[test]
+    fn test_spare_capacity() {
+        let mut buffer: UninitBuffer<u8, 8> = UninitBuffer::new();
+        buffer.write(b"abc");
+        let spare = buffer.spare_capacity_mut();
+        assert_eq!(spare.len(), 5);
+        spare[0].write(b'd');
+        spare[1].write(b'e');
+        unsafe { buffer.commit(2) };
+        assert_eq!(buffer.read(8), b"abcde");
+        assert_eq!(buffer.chunk.init_len(), 5);
+
+        let mut reader = &b"hello world"[..];
+        assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 3);
+        assert_eq!(buffer.read(8), b"hel");
+    }
+
+    #[test]
+    fn test_read_to_ring_after_wrap() {
+        let mut ring: RingBuffer<[u8; 8], crate::buffer::ALLOC, UninitChunk<u8, 8>> =
+            RingBuffer::new();
+        ring.write(&[0; 6]);
+        ring.advance(6);
+        ring.write(b"ab");
+        assert_eq!(ring.spare_capacity_mut().len(), 6);
+        let mut reader = &b"cdefghij"[..];
+        assert_eq!(reader.read_to_buf(&mut ring).unwrap(), 6);
+        assert_eq!(ring.as_slices(), (&b"ab"[..], &b"cdefgh"[..]));
+        assert_eq!(ring.spare_capacity_mut().len(), 0);
+    }
+}
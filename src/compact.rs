@@ -0,0 +1,256 @@
+use core::mem::MaybeUninit;
+
+use crate::{declare_const_impl, Buf, Chunk, ReadBuf, ReadBufMut, WriteBuf, WriteBufferError};
+
+/// Wraps a [`Buf`] and reclaims consumed space without explicit calls to [`Buf::compact`].
+///
+/// Before every write, including [`WriteBuf::spare_capacity_mut`], the buffer is reset if
+/// everything written has been read, or compacted if [`WriteBuf::remaining_space`] is below
+/// `THRESHOLD`. Reads only forward to the inner buffer, so positions taken from [`ReadBuf::pos`]
+/// stay valid until the next write.
+pub struct AutoCompact<B, const THRESHOLD: usize = 0> {
+    buf: B,
+}
+
+impl<B, const THRESHOLD: usize> AutoCompact<B, THRESHOLD> {
+    #[inline(always)]
+    pub const fn new(buf: B) -> Self {
+        Self { buf }
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+
+    #[inline(always)]
+    pub const fn get_ref(&self) -> &B {
+        &self.buf
+    }
+
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.buf
+    }
+}
+
+/// Resets `buf` when it is drained, or compacts it when less than `threshold` space is left.
+macro_rules! reclaim {
+    ($buf:expr, $threshold:expr) => {
+        if $buf.remaining() == 0 {
+            $buf.clear();
+        } else if $buf.remaining_space() < $threshold {
+            $buf.compact();
+        }
+    };
+}
+
+declare_const_impl! {
+    (impl<T, B: Buf<T>, const THRESHOLD: usize> Buf<T> for AutoCompact<B, THRESHOLD>),
+    (impl<T, B: const Buf<T>, const THRESHOLD: usize> const Buf<T> for AutoCompact<B, THRESHOLD>) {
+        #[inline(always)]
+        fn clear(&mut self) {
+            self.buf.clear()
+        }
+
+        #[inline(always)]
+        fn compact(&mut self) {
+            self.buf.compact()
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, B: Buf<T>, const THRESHOLD: usize> WriteBuf<T> for AutoCompact<B, THRESHOLD>),
+    (impl<T, B: const Buf<T>, const THRESHOLD: usize> const WriteBuf<T> for AutoCompact<B, THRESHOLD>) {
+        #[inline(always)]
+        fn write(&mut self, data: &[T]) {
+            reclaim!(self.buf, THRESHOLD);
+            self.buf.write(data)
+        }
+
+        #[inline(always)]
+        fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
+            reclaim!(self.buf, THRESHOLD);
+            self.buf.try_write(data)
+        }
+
+        #[inline(always)]
+        fn try_write_fast<const LEN: usize>(&mut self, data: &[T; LEN]) -> Result<(), WriteBufferError> {
+            reclaim!(self.buf, THRESHOLD);
+            self.buf.try_write_fast::<LEN>(data)
+        }
+
+        /// Includes the space the next write reclaims.
+        #[inline(always)]
+        fn remaining_space(&self) -> usize {
+            let remaining_space = self.buf.remaining_space();
+            if self.buf.remaining() == 0 || remaining_space < THRESHOLD {
+                self.buf.capacity() - self.buf.remaining()
+            } else {
+                remaining_space
+            }
+        }
+
+        #[inline(always)]
+        fn filled_pos(&self) -> usize {
+            self.buf.filled_pos()
+        }
+
+        #[inline(always)]
+        unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+            self.buf.set_filled_pos(filled_pos)
+        }
+
+        #[inline(always)]
+        fn capacity(&self) -> usize {
+            self.buf.capacity()
+        }
+
+        #[inline(always)]
+        fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+            reclaim!(self.buf, THRESHOLD);
+            self.buf.spare_capacity_mut()
+        }
+
+        #[inline(always)]
+        unsafe fn commit(&mut self, len: usize) {
+            self.buf.commit(len)
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, B: Buf<T>, const THRESHOLD: usize> ReadBuf<T> for AutoCompact<B, THRESHOLD>),
+    (impl<T, B: const Buf<T>, const THRESHOLD: usize> const ReadBuf<T> for AutoCompact<B, THRESHOLD>) {
+        #[inline(always)]
+        fn read(&mut self, len: usize) -> &[T] {
+            self.buf.read(len)
+        }
+
+        #[inline(always)]
+        unsafe fn get_continuous(&self, len: usize) -> &[T] {
+            self.buf.get_continuous(len)
+        }
+
+        #[inline(always)]
+        fn remaining(&self) -> usize {
+            self.buf.remaining()
+        }
+
+        #[inline(always)]
+        fn advance(&mut self, len: usize) {
+            self.buf.advance(len)
+        }
+
+        #[inline(always)]
+        fn pos(&self) -> usize {
+            self.buf.pos()
+        }
+
+        #[inline(always)]
+        unsafe fn set_pos(&mut self, pos: usize) {
+            self.buf.set_pos(pos)
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, B: Buf<T>, const THRESHOLD: usize> ReadBufMut<T> for AutoCompact<B, THRESHOLD>),
+    (impl<T, B: const Buf<T>, const THRESHOLD: usize> const ReadBufMut<T> for AutoCompact<B, THRESHOLD>) {
+        #[inline(always)]
+        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+            self.buf.get_continuous_mut(len)
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, B: Buf<T>, const THRESHOLD: usize> Chunk<T> for AutoCompact<B, THRESHOLD>),
+    (impl<T, B: const Buf<T>, const THRESHOLD: usize> const Chunk<T> for AutoCompact<B, THRESHOLD>) {
+        #[inline(always)]
+        fn as_slice(&self) -> &[T] {
+            self.buf.as_slice()
+        }
+
+        #[inline(always)]
+        fn as_mut_slice(&mut self) -> &mut [T] {
+            self.buf.as_mut_slice()
+        }
+
+        #[inline(always)]
+        fn as_ptr(&self) -> *const T {
+            self.buf.as_ptr()
+        }
+
+        #[inline(always)]
+        fn as_mut_ptr(&mut self) -> *mut T {
+            self.buf.as_mut_ptr()
+        }
+
+        #[inline(always)]
+        unsafe fn set_init(&mut self, len: usize) {
+            self.buf.set_init(len)
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::{Buffer, ReadBufCheckpoint, ReadBufExt, ReadBufferError, ReadToBuf};
+
+    use super::*;
+
+    #[test]
+    fn test_reset_when_drained() {
+        let mut buffer = AutoCompact::<Buffer<[u8; 8]>>::new(Buffer::new());
+        buffer.write(b"hello");
+        assert_eq!(buffer.read(5), b"hello");
+        assert_eq!(buffer.pos(), 5);
+        assert_eq!(buffer.remaining_space(), 8);
+
+        buffer.write(b"abcdef");
+        assert_eq!(buffer.pos(), 0);
+        assert_eq!(buffer.read(6), b"abcdef");
+    }
+
+    #[test]
+    fn test_compact_under_threshold() {
+        let mut buffer = AutoCompact::<Buffer<[u8; 8]>, 4>::new(Buffer::new());
+        buffer.write(b"abcdef");
+        buffer.advance(4);
+        assert_eq!(buffer.pos(), 4);
+        assert_eq!(buffer.remaining_space(), 6);
+
+        buffer.try_write(b"ghij").unwrap();
+        assert_eq!(buffer.pos(), 0);
+        assert_eq!(buffer.read(6), b"efghij");
+    }
+
+    #[test]
+    fn test_rollback_after_drain() {
+        let mut buffer = AutoCompact::<Buffer<[u8; 8]>>::new(Buffer::new());
+        buffer.write(&[1, 2]);
+        assert_eq!(
+            buffer.read_transaction(|buffer| {
+                buffer.try_read_u16_be()?;
+                buffer.try_read_u8()
+            }),
+            Err(ReadBufferError::BufferUnderflow)
+        );
+        assert_eq!(buffer.remaining(), 2);
+        assert_eq!(buffer.try_read_u16_be(), Ok(0x0102));
+    }
+
+    #[test]
+    fn test_read_to_buf_after_drain() {
+        let mut buffer = AutoCompact::<Buffer<[u8; 8]>>::new(Buffer::new());
+        let mut reader = &b"abcdefghijklmnop"[..];
+        assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 8);
+        buffer.advance(8);
+        assert_eq!(buffer.remaining_space(), 8);
+        assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 8);
+        assert_eq!(buffer.read(8), b"ijklmnop");
+    }
+}
@@ -0,0 +1,134 @@
+use memchr::{memchr, memmem};
+
+use crate::ReadBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelimiterError {
+    /// No delimiter yet, read more and try again.
+    Incomplete,
+    /// No delimiter within the first `max_len` bytes of the token.
+    TooLong,
+    /// The readable region wraps around before a delimiter was found,
+    /// [`Buf::compact`](crate::Buf::compact) makes it continuous again.
+    NotContinuous,
+}
+
+impl core::fmt::Display for DelimiterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DelimiterError::Incomplete => f.write_str("delimiter not found yet"),
+            DelimiterError::TooLong => f.write_str("token exceeds the maximum length"),
+            DelimiterError::NotContinuous => f.write_str("readable region is not continuous"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DelimiterError {}
+
+/// Delimiter based framing for byte buffers, searching [`ReadBuf::get_continuous`] with memchr.
+///
+/// Tokens are borrowed from the buffer and `pos` only moves past a token once its delimiter
+/// was found. `max_len` limits the token length without the delimiter.
+pub trait ReadDelimited: ReadBuf<u8> {
+    /// Reads the token before `delim` and consumes the delimiter as well.
+    #[inline(always)]
+    fn read_until(&mut self, delim: &[u8], max_len: usize) -> Result<&[u8], DelimiterError> {
+        let len = find_delimiter(self, delim, max_len)?;
+        Ok(&self.read(len + delim.len())[..len])
+    }
+
+    /// Reads a line ending in `\n` or `\r\n`, the line ending is consumed but not returned.
+    #[inline(always)]
+    fn read_line(&mut self, max_len: usize) -> Result<&[u8], DelimiterError> {
+        // Leave room for the `\r` of a `\r\n`, which doesn't count towards `max_len`.
+        let len = find_delimiter(self, b"\n", max_len.saturating_add(1))?;
+        let line_len = match unsafe { self.get_continuous(len) }.last() {
+            Some(b'\r') => len - 1,
+            _ if len > max_len => return Err(DelimiterError::TooLong),
+            _ => len,
+        };
+        Ok(&self.read(len + 1)[..line_len])
+    }
+
+    /// Consumes everything up to and including `delim`, returning the length of the skipped token.
+    #[inline(always)]
+    fn skip_until(&mut self, delim: &[u8], max_len: usize) -> Result<usize, DelimiterError> {
+        let len = find_delimiter(self, delim, max_len)?;
+        self.advance(len + delim.len());
+        Ok(len)
+    }
+}
+
+impl<R: ReadBuf<u8> + ?Sized> ReadDelimited for R {}
+
+#[inline(always)]
+fn find_delimiter<R: ReadBuf<u8> + ?Sized>(
+    buf: &R,
+    delim: &[u8],
+    max_len: usize,
+) -> Result<usize, DelimiterError> {
+    assert!(!delim.is_empty(), "delimiter must not be empty");
+    let window_len = max_len.saturating_add(delim.len());
+    let continuous = unsafe { buf.get_continuous(window_len) };
+    let found = match delim {
+        [byte] => memchr(*byte, continuous),
+        _ => memmem::find(continuous, delim),
+    };
+    match found {
+        Some(len) => Ok(len),
+        None if continuous.len() == window_len => Err(DelimiterError::TooLong),
+        None if continuous.len() < buf.remaining() => Err(DelimiterError::NotContinuous),
+        None => Err(DelimiterError::Incomplete),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{Buf, Buffer, RingBuffer, WriteBuf};
+
+    #[test]
+    fn test_read_line() {
+        let mut buffer: Buffer<[u8; 64]> = Buffer::new();
+        buffer.write(b"EHLO example.com\r\nPING\nQU");
+        assert_eq!(buffer.read_line(64), Ok(&b"EHLO example.com"[..]));
+        assert_eq!(buffer.read_line(64), Ok(&b"PING"[..]));
+        assert_eq!(buffer.read_line(64), Err(DelimiterError::Incomplete));
+        assert_eq!(buffer.remaining(), 2);
+        buffer.write(b"IT\r\n");
+        assert_eq!(buffer.read_line(4), Ok(&b"QUIT"[..]));
+        buffer.write(b"HELP\r\n");
+        assert_eq!(buffer.read_line(3), Err(DelimiterError::TooLong));
+        buffer.write(b"HELP\n");
+        assert_eq!(buffer.skip_until(b"\r\n", 4), Ok(4));
+        assert_eq!(buffer.read_line(4), Ok(&b"HELP"[..]));
+    }
+
+    #[test]
+    fn test_max_len_and_multi_byte_delimiter() {
+        let mut buffer: Buffer<[u8; 64]> = Buffer::new();
+        buffer.write(b"key: value\r\n\r\nbody");
+        assert_eq!(
+            buffer.read_until(b"\r\n\r\n", 9),
+            Err(DelimiterError::TooLong)
+        );
+        assert_eq!(buffer.skip_until(b"\r\n\r\n", 10), Ok(10));
+        assert_eq!(buffer.read_until(b"\0", 3), Err(DelimiterError::TooLong));
+        assert_eq!(buffer.read_until(b"\0", 4), Err(DelimiterError::Incomplete));
+        assert_eq!(buffer.remaining(), 4);
+    }
+
+    #[test]
+    fn test_not_continuous() {
+        let mut buffer: RingBuffer<[u8; 8]> = RingBuffer::new();
+        buffer.write(&[0; 6]);
+        buffer.advance(6);
+        buffer.write(b"ab\ncd");
+        assert_eq!(buffer.read_line(8), Err(DelimiterError::NotContinuous));
+        buffer.compact();
+        assert_eq!(buffer.read_line(8), Ok(&b"ab"[..]));
+        assert_eq!(buffer.read_line(8), Err(DelimiterError::Incomplete));
+    }
+}
@@ -0,0 +1,794 @@
+use core::mem::MaybeUninit;
+
+use crate::{
+    ReadBuf, ReadBufExt, ReadBufferError, ReadVarInt, VarIntError, WriteBuf, WriteBufferError,
+    WriteVarInt,
+};
+
+/// Binary encoding written straight into a [`WriteBuf`].
+///
+/// Numbers are little endian, `usize`/`isize` as 8 bytes. Lengths of `Vec` and `String` are LEB128
+/// varints. On error part of the value may have been written already, wrap the call in
+/// [`WriteBufCheckpoint::write_transaction`](crate::WriteBufCheckpoint::write_transaction)
+/// to undo it.
+pub trait Encode {
+    /// Number of bytes every value of the type encodes to, `None` when it depends on the value.
+    ///
+    /// `#[derive(Encode)]` on a non-generic type with a fixed size writes it with a single
+    /// [`WriteBuf::try_write_fast`] call.
+    const ENCODED_SIZE: Option<usize> = None;
+
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError>;
+
+    /// Writes the value into `dst` of exactly `ENCODED_SIZE` bytes.
+    ///
+    /// Only called when `ENCODED_SIZE` is `Some`, which is when it must be implemented.
+    fn encode_fixed(&self, dst: &mut [u8]) {
+        let _ = dst;
+        unreachable!("encode_fixed called on a type without a fixed encoded size")
+    }
+}
+
+/// Reads values written by [`Encode`] from a [`ReadBuf`].
+///
+/// Fixed size values consume nothing when the buffer runs short, otherwise part of the value may
+/// have been consumed on error, see [`ReadBufCheckpoint::read_transaction`](crate::ReadBufCheckpoint::read_transaction).
+pub trait Decode: Sized {
+    /// Same as [`Encode::ENCODED_SIZE`].
+    const ENCODED_SIZE: Option<usize> = None;
+
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError>;
+
+    /// Reads the value from `src` of exactly `ENCODED_SIZE` bytes.
+    ///
+    /// Only called when `ENCODED_SIZE` is `Some`, which is when it must be implemented.
+    fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+        let _ = src;
+        unreachable!("decode_fixed called on a type without a fixed encoded size")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer ended in the middle of a value.
+    BufferUnderflow,
+    InvalidBool(u8),
+    InvalidChar(u32),
+    InvalidUtf8,
+    /// The enum or `Option` tag doesn't name a variant.
+    InvalidTag(u32),
+    InvalidLength,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::BufferUnderflow => f.write_str("not enough data in buffer"),
+            DecodeError::InvalidBool(byte) => write!(f, "invalid bool: {byte}"),
+            DecodeError::InvalidChar(value) => write!(f, "invalid char: {value:#x}"),
+            DecodeError::InvalidUtf8 => f.write_str("invalid utf-8"),
+            DecodeError::InvalidTag(tag) => write!(f, "invalid tag: {tag}"),
+            DecodeError::InvalidLength => f.write_str("invalid length"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+impl From<ReadBufferError> for DecodeError {
+    fn from(err: ReadBufferError) -> Self {
+        match err {
+            ReadBufferError::BufferUnderflow => DecodeError::BufferUnderflow,
+        }
+    }
+}
+
+impl From<VarIntError> for DecodeError {
+    fn from(err: VarIntError) -> Self {
+        match err {
+            VarIntError::Incomplete => DecodeError::BufferUnderflow,
+            VarIntError::Overlong => DecodeError::InvalidLength,
+        }
+    }
+}
+
+/// Sum of `sizes`, `None` if any of them is.
+pub const fn sum_encoded_sizes(sizes: &[Option<usize>]) -> Option<usize> {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < sizes.len() {
+        match sizes[i] {
+            Some(size) => sum += size,
+            None => return None,
+        }
+        i += 1;
+    }
+    Some(sum)
+}
+
+/// The size shared by all of `sizes`, `None` if they differ or any of them is `None`.
+pub const fn same_encoded_size(sizes: &[Option<usize>]) -> Option<usize> {
+    let Some(Some(first)) = sizes.first().copied() else {
+        return None;
+    };
+    let mut i = 1;
+    while i < sizes.len() {
+        match sizes[i] {
+            Some(size) if size == first => {}
+            _ => return None,
+        }
+        i += 1;
+    }
+    Some(first)
+}
+
+/// `size` or zero, for array lengths that are only used when `size` is `Some`.
+pub const fn fixed_size(size: Option<usize>) -> usize {
+    match size {
+        Some(size) => size,
+        None => 0,
+    }
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                const ENCODED_SIZE: Option<usize> = Some(size_of::<$ty>());
+
+                #[inline(always)]
+                fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+                    buf.try_write_fast(&self.to_le_bytes())
+                }
+
+                #[inline(always)]
+                fn encode_fixed(&self, dst: &mut [u8]) {
+                    dst.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl Decode for $ty {
+                const ENCODED_SIZE: Option<usize> = Some(size_of::<$ty>());
+
+                #[inline(always)]
+                fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+                    Ok(<$ty>::from_le_bytes(buf.try_read_array()?))
+                }
+
+                #[inline(always)]
+                fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+                    Ok(<$ty>::from_le_bytes(src.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+
+macro_rules! impl_size {
+    ($($ty:ty => $wide:ty;)*) => {
+        $(
+            impl Encode for $ty {
+                const ENCODED_SIZE: Option<usize> = <$wide as Encode>::ENCODED_SIZE;
+
+                #[inline(always)]
+                fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+                    (*self as $wide).encode(buf)
+                }
+
+                #[inline(always)]
+                fn encode_fixed(&self, dst: &mut [u8]) {
+                    (*self as $wide).encode_fixed(dst)
+                }
+            }
+
+            impl Decode for $ty {
+                const ENCODED_SIZE: Option<usize> = <$wide as Decode>::ENCODED_SIZE;
+
+                #[inline(always)]
+                fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+                    <$ty>::try_from(<$wide>::decode(buf)?).map_err(|_| DecodeError::InvalidLength)
+                }
+
+                #[inline(always)]
+                fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+                    <$ty>::try_from(<$wide>::decode_fixed(src)?).map_err(|_| DecodeError::InvalidLength)
+                }
+            }
+        )*
+    };
+}
+
+impl_size! {
+    usize => u64;
+    isize => i64;
+}
+
+impl Encode for bool {
+    const ENCODED_SIZE: Option<usize> = Some(1);
+
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        (*self as u8).encode(buf)
+    }
+
+    #[inline(always)]
+    fn encode_fixed(&self, dst: &mut [u8]) {
+        dst[0] = *self as u8;
+    }
+}
+
+impl Decode for bool {
+    const ENCODED_SIZE: Option<usize> = Some(1);
+
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        Self::decode_fixed(&buf.try_read_array::<1>()?)
+    }
+
+    #[inline(always)]
+    fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+        match src[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(DecodeError::InvalidBool(byte)),
+        }
+    }
+}
+
+impl Encode for char {
+    const ENCODED_SIZE: Option<usize> = Some(4);
+
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        (*self as u32).encode(buf)
+    }
+
+    #[inline(always)]
+    fn encode_fixed(&self, dst: &mut [u8]) {
+        (*self as u32).encode_fixed(dst)
+    }
+}
+
+impl Decode for char {
+    const ENCODED_SIZE: Option<usize> = Some(4);
+
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        Self::decode_fixed(&buf.try_read_array::<4>()?)
+    }
+
+    #[inline(always)]
+    fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+        let value = u32::decode_fixed(src)?;
+        char::from_u32(value).ok_or(DecodeError::InvalidChar(value))
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    const ENCODED_SIZE: Option<usize> = T::ENCODED_SIZE;
+
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        (**self).encode(buf)
+    }
+
+    #[inline(always)]
+    fn encode_fixed(&self, dst: &mut [u8]) {
+        (**self).encode_fixed(dst)
+    }
+}
+
+/// Writes and reads runs of elements, in bulk for bytes.
+trait EncodeSlice: Sized {
+    fn encode_slice<W: WriteBuf<u8> + ?Sized>(
+        slice: &[Self],
+        buf: &mut W,
+    ) -> Result<(), WriteBufferError>;
+}
+
+impl<T: Encode> EncodeSlice for T {
+    #[inline(always)]
+    default fn encode_slice<W: WriteBuf<u8> + ?Sized>(
+        slice: &[Self],
+        buf: &mut W,
+    ) -> Result<(), WriteBufferError> {
+        for value in slice {
+            value.encode(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl EncodeSlice for u8 {
+    #[inline(always)]
+    fn encode_slice<W: WriteBuf<u8> + ?Sized>(
+        slice: &[Self],
+        buf: &mut W,
+    ) -> Result<(), WriteBufferError> {
+        buf.try_write(slice)
+    }
+}
+
+trait DecodeSlice: Sized {
+    /// Fills all of `dst`, leaving the initialized prefix behind on error.
+    fn decode_slice<R: ReadBuf<u8> + ?Sized>(
+        dst: &mut [MaybeUninit<Self>],
+        buf: &mut R,
+        init: &mut usize,
+    ) -> Result<(), DecodeError>;
+}
+
+impl<T: Decode> DecodeSlice for T {
+    #[inline(always)]
+    default fn decode_slice<R: ReadBuf<u8> + ?Sized>(
+        dst: &mut [MaybeUninit<Self>],
+        buf: &mut R,
+        init: &mut usize,
+    ) -> Result<(), DecodeError> {
+        for slot in dst {
+            slot.write(T::decode(buf)?);
+            *init += 1;
+        }
+        Ok(())
+    }
+}
+
+impl DecodeSlice for u8 {
+    #[inline(always)]
+    fn decode_slice<R: ReadBuf<u8> + ?Sized>(
+        dst: &mut [MaybeUninit<Self>],
+        buf: &mut R,
+        init: &mut usize,
+    ) -> Result<(), DecodeError> {
+        if buf.remaining() < dst.len() {
+            return Err(DecodeError::BufferUnderflow);
+        }
+        while *init < dst.len() {
+            let slice = buf.read(dst.len() - *init);
+            dst[*init..*init + slice.len()].write_copy_of_slice(slice);
+            *init += slice.len();
+        }
+        Ok(())
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    const ENCODED_SIZE: Option<usize> = match T::ENCODED_SIZE {
+        Some(size) => Some(size * N),
+        None => None,
+    };
+
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        T::encode_slice(self, buf)
+    }
+
+    #[inline(always)]
+    fn encode_fixed(&self, dst: &mut [u8]) {
+        let size = fixed_size(T::ENCODED_SIZE);
+        for (i, value) in self.iter().enumerate() {
+            value.encode_fixed(&mut dst[i * size..(i + 1) * size]);
+        }
+    }
+}
+
+/// Drops the initialized prefix of an array that failed to decode.
+struct PartialArray<'a, T> {
+    slots: &'a mut [MaybeUninit<T>],
+    init: usize,
+}
+
+impl<T> Drop for PartialArray<'_, T> {
+    fn drop(&mut self) {
+        unsafe { self.slots[..self.init].assume_init_drop() };
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    const ENCODED_SIZE: Option<usize> = match T::ENCODED_SIZE {
+        Some(size) => Some(size * N),
+        None => None,
+    };
+
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        let mut array = [const { MaybeUninit::uninit() }; N];
+        let mut partial = PartialArray {
+            slots: &mut array,
+            init: 0,
+        };
+        T::decode_slice(partial.slots, buf, &mut partial.init)?;
+        core::mem::forget(partial);
+        Ok(unsafe { (&array as *const [MaybeUninit<T>; N] as *const [T; N]).read() })
+    }
+
+    #[inline(always)]
+    fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+        let size = fixed_size(T::ENCODED_SIZE);
+        let mut array = [const { MaybeUninit::uninit() }; N];
+        let mut partial = PartialArray {
+            slots: &mut array,
+            init: 0,
+        };
+        while partial.init < N {
+            let i = partial.init;
+            partial.slots[i].write(T::decode_fixed(&src[i * size..(i + 1) * size])?);
+            partial.init += 1;
+        }
+        core::mem::forget(partial);
+        Ok(unsafe { (&array as *const [MaybeUninit<T>; N] as *const [T; N]).read() })
+    }
+}
+
+/// Splits the input of [`Decode::decode_fixed`] between fields.
+struct FixedSrc<'a>(&'a [u8]);
+
+impl<'a> FixedSrc<'a> {
+    #[inline(always)]
+    fn take(&mut self, size: Option<usize>) -> &'a [u8] {
+        let (head, tail) = self.0.split_at(fixed_size(size));
+        self.0 = tail;
+        head
+    }
+}
+
+macro_rules! impl_tuple {
+    ($(($($name:ident $index:tt),+);)*) => {
+        $(
+            impl<$($name: Encode),+> Encode for ($($name,)+) {
+                const ENCODED_SIZE: Option<usize> = sum_encoded_sizes(&[$($name::ENCODED_SIZE),+]);
+
+                #[inline(always)]
+                fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+                    $(self.$index.encode(buf)?;)+
+                    Ok(())
+                }
+
+                #[inline(always)]
+                fn encode_fixed(&self, dst: &mut [u8]) {
+                    $(
+                        let (head, dst) = dst.split_at_mut(fixed_size($name::ENCODED_SIZE));
+                        self.$index.encode_fixed(head);
+                    )+
+                    debug_assert!(dst.is_empty());
+                }
+            }
+
+            impl<$($name: Decode),+> Decode for ($($name,)+) {
+                const ENCODED_SIZE: Option<usize> = sum_encoded_sizes(&[$($name::ENCODED_SIZE),+]);
+
+                #[inline(always)]
+                fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+                    Ok(($($name::decode(buf)?,)+))
+                }
+
+                #[inline(always)]
+                fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+                    let mut src = FixedSrc(src);
+                    Ok(($($name::decode_fixed(src.take($name::ENCODED_SIZE))?,)+))
+                }
+            }
+        )*
+    };
+}
+
+impl_tuple! {
+    (A 0);
+    (A 0, B 1);
+    (A 0, B 1, C 2);
+    (A 0, B 1, C 2, D 3);
+    (A 0, B 1, C 2, D 3, E 4);
+    (A 0, B 1, C 2, D 3, E 4, F 5);
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+}
+
+impl Encode for () {
+    const ENCODED_SIZE: Option<usize> = Some(0);
+
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, _buf: &mut W) -> Result<(), WriteBufferError> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn encode_fixed(&self, _dst: &mut [u8]) {}
+}
+
+impl Decode for () {
+    const ENCODED_SIZE: Option<usize> = Some(0);
+
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(_buf: &mut R) -> Result<Self, DecodeError> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn decode_fixed(_src: &[u8]) -> Result<Self, DecodeError> {
+        Ok(())
+    }
+}
+
+/// A `u8` tag of 0 or 1 followed by the value.
+impl<T: Encode> Encode for Option<T> {
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        match self {
+            None => 0u8.encode(buf),
+            Some(value) => {
+                1u8.encode(buf)?;
+                value.encode(buf)
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        match u8::decode(buf)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(buf)?)),
+            tag => Err(DecodeError::InvalidTag(tag as u32)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Encode + ?Sized> Encode for Box<T> {
+    const ENCODED_SIZE: Option<usize> = T::ENCODED_SIZE;
+
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        (**self).encode(buf)
+    }
+
+    #[inline(always)]
+    fn encode_fixed(&self, dst: &mut [u8]) {
+        (**self).encode_fixed(dst)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Decode> Decode for Box<T> {
+    const ENCODED_SIZE: Option<usize> = T::ENCODED_SIZE;
+
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        T::decode(buf).map(Box::new)
+    }
+
+    #[inline(always)]
+    fn decode_fixed(src: &[u8]) -> Result<Self, DecodeError> {
+        T::decode_fixed(src).map(Box::new)
+    }
+}
+
+/// Longest `Vec` of elements encoded in zero bytes, e.g. `Vec<()>`, that [`Decode`] accepts.
+pub const MAX_ZERO_SIZED_LEN: usize = 0x10000;
+
+/// A varint length followed by the elements.
+impl<T: Encode> Encode for [T] {
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        buf.try_write_var_u64(self.len() as u64)?;
+        T::encode_slice(self, buf)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Encode> Encode for Vec<T> {
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        self.as_slice().encode(buf)
+    }
+}
+
+/// Elements encoded in zero bytes don't consume any input, so their count is capped at
+/// [`MAX_ZERO_SIZED_LEN`] instead of by the input.
+#[cfg(feature = "std")]
+impl<T: Decode> Decode for Vec<T> {
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        let len = decode_len(buf)?;
+        if (size_of::<T>() == 0 || T::ENCODED_SIZE == Some(0)) && len > MAX_ZERO_SIZED_LEN {
+            return Err(DecodeError::InvalidLength);
+        }
+        // Don't trust the length for the allocation, the elements may not be there.
+        let mut vec = Vec::with_capacity(len.min(buf.remaining()));
+        while vec.len() < len {
+            if vec.len() == vec.capacity() {
+                vec.reserve(1);
+            }
+            let chunk_len = (vec.capacity() - vec.len()).min(len - vec.len());
+            let mut init = 0;
+            let result =
+                T::decode_slice(&mut vec.spare_capacity_mut()[..chunk_len], buf, &mut init);
+            unsafe { vec.set_len(vec.len() + init) };
+            result?;
+        }
+        Ok(vec)
+    }
+}
+
+impl Encode for str {
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        self.as_bytes().encode(buf)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Encode for String {
+    #[inline(always)]
+    fn encode<W: WriteBuf<u8> + ?Sized>(&self, buf: &mut W) -> Result<(), WriteBufferError> {
+        self.as_bytes().encode(buf)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Decode for String {
+    #[inline(always)]
+    fn decode<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<Self, DecodeError> {
+        String::from_utf8(Vec::decode(buf)?).map_err(|_| DecodeError::InvalidUtf8)
+    }
+}
+
+#[inline(always)]
+fn decode_len<R: ReadBuf<u8> + ?Sized>(buf: &mut R) -> Result<usize, DecodeError> {
+    usize::try_from(buf.try_read_var_u64()?).map_err(|_| DecodeError::InvalidLength)
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{Buffer, RingBuffer, WriteBufCheckpoint};
+
+    #[test]
+    fn test_builtin_round_trip() {
+        let mut buffer: RingBuffer<[u8; 128]> = RingBuffer::new();
+        buffer.write(&[0; 100]);
+        buffer.advance(100);
+        let value = (
+            -3i16,
+            [true, false],
+            'é',
+            Some(Box::new(1.5f32)),
+            vec![String::from("ab"), String::new()],
+            usize::MAX,
+        );
+        value.encode(&mut buffer).unwrap();
+        assert_eq!(
+            <(i16, [bool; 2], char) as Encode>::ENCODED_SIZE,
+            Some(2 + 2 + 4)
+        );
+        assert_eq!(Decode::decode(&mut buffer), Ok(value));
+        assert_eq!(buffer.remaining(), 0);
+
+        buffer.write(&[2]);
+        assert_eq!(
+            Option::<u8>::decode(&mut buffer),
+            Err(DecodeError::InvalidTag(2))
+        );
+    }
+
+    #[test]
+    fn test_underflow() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        assert_eq!(
+            buffer.write_transaction(|buf| vec![0u8; 32].encode(buf)),
+            Err(WriteBufferError::BufferFull)
+        );
+        assert_eq!(buffer.remaining(), 0);
+
+        buffer.write(&[2, 1, 0]);
+        assert_eq!(u32::decode(&mut buffer), Err(DecodeError::BufferUnderflow));
+        assert_eq!(buffer.remaining(), 3);
+        assert_eq!(
+            Vec::<u16>::decode(&mut buffer),
+            Err(DecodeError::BufferUnderflow)
+        );
+    }
+
+    #[test]
+    fn test_zero_sized_len() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        vec![(); 3].encode(&mut buffer).unwrap();
+        assert_eq!(Vec::<()>::decode(&mut buffer), Ok(vec![(); 3]));
+
+        buffer.try_write_var_u64(u64::MAX).unwrap();
+        assert_eq!(
+            Vec::<()>::decode(&mut buffer),
+            Err(DecodeError::InvalidLength)
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive() {
+        use crate::{Decode, Encode};
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        struct Position {
+            x: i32,
+            y: i32,
+            on_ground: bool,
+        }
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        enum Direction {
+            Up,
+            Down(u8),
+        }
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        struct Marker;
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        struct Empty((), [u8; 0]);
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        enum Packet<T> {
+            Move(Position, Direction),
+            Chat { message: String, extra: T },
+            Ping,
+        }
+
+        assert_eq!(<Position as Encode>::ENCODED_SIZE, Some(9));
+        assert_eq!(<Direction as Encode>::ENCODED_SIZE, None);
+        assert_eq!(<Packet<u8> as Encode>::ENCODED_SIZE, None);
+        assert_eq!(<Marker as Encode>::ENCODED_SIZE, Some(0));
+
+        let mut buffer: Buffer<[u8; 64]> = Buffer::new();
+        Marker.encode(&mut buffer).unwrap();
+        Empty((), []).encode(&mut buffer).unwrap();
+        assert_eq!(buffer.remaining(), 0);
+        assert_eq!(Marker::decode(&mut buffer), Ok(Marker));
+        assert_eq!(Empty::decode(&mut buffer), Ok(Empty((), [])));
+
+        let position = Position {
+            x: 1,
+            y: -2,
+            on_ground: true,
+        };
+        position.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.remaining(), 9);
+        assert_eq!(Position::decode(&mut buffer), Ok(position));
+
+        let packets = [
+            Packet::Move(
+                Position {
+                    x: 3,
+                    y: 4,
+                    on_ground: false,
+                },
+                Direction::Down(7),
+            ),
+            Packet::Chat {
+                message: "hi".into(),
+                extra: 9u64,
+            },
+            Packet::Ping,
+        ];
+        packets.encode(&mut buffer).unwrap();
+        assert_eq!(Decode::decode(&mut buffer), Ok(packets));
+
+        buffer.write(&[0, 0, 0, 0, 0, 0, 0, 0, 2, 2]);
+        assert_eq!(
+            Position::decode(&mut buffer),
+            Err(DecodeError::InvalidBool(2))
+        );
+        assert_eq!(
+            Direction::decode(&mut buffer),
+            Err(DecodeError::InvalidTag(2))
+        );
+    }
+}
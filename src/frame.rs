@@ -0,0 +1,283 @@
+use crate::{
+    ReadBuf, ReadBufExt, ReadVarInt, VarIntError, WriteBuf, WriteBufExt, WriteBufferError,
+    WriteVarInt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// Encoding of the payload length in front of every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+    U8,
+    U16(Endian),
+    U32(Endian),
+    U64(Endian),
+    /// Unsigned LEB128, see [`ReadVarInt`].
+    VarInt,
+}
+
+impl LengthPrefix {
+    /// Largest payload length the prefix can hold.
+    pub const fn max_len(self) -> u64 {
+        match self {
+            LengthPrefix::U8 => u8::MAX as u64,
+            LengthPrefix::U16(_) => u16::MAX as u64,
+            LengthPrefix::U32(_) => u32::MAX as u64,
+            LengthPrefix::U64(_) | LengthPrefix::VarInt => u64::MAX,
+        }
+    }
+
+    /// Number of bytes the prefix takes for a payload of `len` bytes.
+    pub const fn encoded_len(self, len: u64) -> usize {
+        match self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16(_) => 2,
+            LengthPrefix::U32(_) => 4,
+            LengthPrefix::U64(_) => 8,
+            LengthPrefix::VarInt => crate::var_u64_len(len),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// At least `needed` more bytes are required before the frame is complete.
+    Incomplete {
+        needed: usize,
+    },
+    /// The frame is longer than the prefix or the maximum frame length allows.
+    TooLarge {
+        len: u64,
+    },
+    /// The LEB128 prefix is longer than a `u64`.
+    InvalidPrefix,
+    Write(WriteBufferError),
+}
+
+impl core::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FrameError::Incomplete { needed } => write!(f, "frame needs {needed} more bytes"),
+            FrameError::TooLarge { len } => write!(f, "frame of {len} bytes is too large"),
+            FrameError::InvalidPrefix => f.write_str("invalid length prefix"),
+            FrameError::Write(err) => write!(f, "write failed: {err:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+impl From<WriteBufferError> for FrameError {
+    fn from(err: WriteBufferError) -> Self {
+        FrameError::Write(err)
+    }
+}
+
+/// Complete frame taken out of a buffer by [`FrameCodec::decode`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Frame<'a> {
+    /// The payload, `pos` is past the frame.
+    Borrowed(&'a [u8]),
+    /// The payload isn't continuous, e.g. it wraps around a [`RingBuffer`](crate::RingBuffer).
+    ///
+    /// Only the prefix was consumed, the next `len` bytes of the buffer are the payload.
+    Split { len: usize },
+}
+
+/// Length prefixed framing, see [`LengthPrefix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameCodec {
+    prefix: LengthPrefix,
+    max_frame_len: Option<usize>,
+}
+
+impl FrameCodec {
+    #[inline(always)]
+    pub const fn new(prefix: LengthPrefix) -> Self {
+        Self {
+            prefix,
+            max_frame_len: None,
+        }
+    }
+
+    /// Rejects payloads longer than `max_frame_len` on both ends.
+    #[inline(always)]
+    pub const fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = Some(max_frame_len);
+        self
+    }
+
+    #[inline(always)]
+    pub const fn prefix(&self) -> LengthPrefix {
+        self.prefix
+    }
+
+    #[inline(always)]
+    pub const fn max_frame_len(&self) -> Option<usize> {
+        self.max_frame_len
+    }
+
+    /// Takes the next frame out of `buf`.
+    ///
+    /// Consumes nothing on error. [`FrameError::TooLarge`] is reported as soon as the prefix is
+    /// readable, so the connection can be dropped before the payload arrives.
+    pub fn decode<'a, R: ReadBuf<u8> + ?Sized>(
+        &self,
+        buf: &'a mut R,
+    ) -> Result<Frame<'a>, FrameError> {
+        let start = buf.pos();
+        let remaining = buf.remaining();
+        let len = self.read_prefix(buf)?;
+        let prefix_len = remaining - buf.remaining();
+        let len = match self.check_len(len) {
+            Ok(len) => len,
+            Err(err) => {
+                unsafe { buf.set_pos(start) };
+                return Err(err);
+            }
+        };
+        if buf.remaining() < len {
+            unsafe { buf.set_pos(start) };
+            return Err(FrameError::Incomplete {
+                needed: prefix_len + len - remaining,
+            });
+        }
+        if unsafe { buf.get_continuous(len) }.len() == len {
+            Ok(Frame::Borrowed(buf.read(len)))
+        } else {
+            Ok(Frame::Split { len })
+        }
+    }
+
+    /// Writes the prefix and `payload`, writing nothing on error.
+    pub fn encode<W: WriteBuf<u8> + ?Sized>(
+        &self,
+        payload: &[u8],
+        buf: &mut W,
+    ) -> Result<(), FrameError> {
+        let len = self.check_len(payload.len() as u64)? as u64;
+        if buf.remaining_space() < self.prefix.encoded_len(len) + payload.len() {
+            return Err(WriteBufferError::BufferFull.into());
+        }
+        match self.prefix {
+            LengthPrefix::U8 => buf.try_write_u8(len as u8),
+            LengthPrefix::U16(Endian::Big) => buf.try_write_u16_be(len as u16),
+            LengthPrefix::U16(Endian::Little) => buf.try_write_u16_le(len as u16),
+            LengthPrefix::U32(Endian::Big) => buf.try_write_u32_be(len as u32),
+            LengthPrefix::U32(Endian::Little) => buf.try_write_u32_le(len as u32),
+            LengthPrefix::U64(Endian::Big) => buf.try_write_u64_be(len),
+            LengthPrefix::U64(Endian::Little) => buf.try_write_u64_le(len),
+            LengthPrefix::VarInt => buf.try_write_var_u64(len),
+        }?;
+        Ok(buf.try_write(payload)?)
+    }
+
+    #[inline(always)]
+    fn read_prefix<R: ReadBuf<u8> + ?Sized>(&self, buf: &mut R) -> Result<u64, FrameError> {
+        let needed = self.prefix.encoded_len(0).saturating_sub(buf.remaining());
+        let incomplete = |_| FrameError::Incomplete { needed };
+        match self.prefix {
+            LengthPrefix::U8 => buf.try_read_u8().map(u64::from).map_err(incomplete),
+            LengthPrefix::U16(Endian::Big) => {
+                buf.try_read_u16_be().map(u64::from).map_err(incomplete)
+            }
+            LengthPrefix::U16(Endian::Little) => {
+                buf.try_read_u16_le().map(u64::from).map_err(incomplete)
+            }
+            LengthPrefix::U32(Endian::Big) => {
+                buf.try_read_u32_be().map(u64::from).map_err(incomplete)
+            }
+            LengthPrefix::U32(Endian::Little) => {
+                buf.try_read_u32_le().map(u64::from).map_err(incomplete)
+            }
+            LengthPrefix::U64(Endian::Big) => buf.try_read_u64_be().map_err(incomplete),
+            LengthPrefix::U64(Endian::Little) => buf.try_read_u64_le().map_err(incomplete),
+            LengthPrefix::VarInt => buf.try_read_var_u64().map_err(|err| match err {
+                VarIntError::Incomplete => FrameError::Incomplete { needed: 1 },
+                VarIntError::Overlong => FrameError::InvalidPrefix,
+            }),
+        }
+    }
+
+    #[inline(always)]
+    fn check_len(&self, len: u64) -> Result<usize, FrameError> {
+        let max = match self.max_frame_len {
+            Some(max) => self.prefix.max_len().min(max as u64),
+            None => self.prefix.max_len(),
+        };
+        match usize::try_from(len) {
+            Ok(frame_len) if len <= max => Ok(frame_len),
+            _ => Err(FrameError::TooLarge { len }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{Buffer, RingBuffer};
+
+    #[test]
+    fn test_incomplete_then_borrowed() {
+        let codec = FrameCodec::new(LengthPrefix::U16(Endian::Big));
+        let mut buffer: Buffer<[u8; 32]> = Buffer::new();
+        buffer.write(&[0]);
+        assert_eq!(
+            codec.decode(&mut buffer),
+            Err(FrameError::Incomplete { needed: 1 })
+        );
+        buffer.write(&[5, b'h', b'e']);
+        assert_eq!(
+            codec.decode(&mut buffer),
+            Err(FrameError::Incomplete { needed: 3 })
+        );
+        assert_eq!(buffer.remaining(), 4);
+        buffer.write(b"llo");
+        codec.encode(b"", &mut buffer).unwrap();
+        assert_eq!(codec.decode(&mut buffer), Ok(Frame::Borrowed(b"hello")));
+        assert_eq!(codec.decode(&mut buffer), Ok(Frame::Borrowed(b"")));
+        assert_eq!(buffer.remaining(), 0);
+    }
+
+    #[test]
+    fn test_split_across_ring_wrap() {
+        let codec = FrameCodec::new(LengthPrefix::VarInt);
+        let mut buffer: RingBuffer<[u8; 16]> = RingBuffer::new();
+        buffer.write(&[0; 12]);
+        buffer.advance(12);
+        codec.encode(b"abcdef", &mut buffer).unwrap();
+        assert_eq!(codec.decode(&mut buffer), Ok(Frame::Split { len: 6 }));
+        assert_eq!(buffer.as_slices(), (&b"abc"[..], &b"def"[..]));
+    }
+
+    #[test]
+    fn test_max_frame_len() {
+        let codec = FrameCodec::new(LengthPrefix::U32(Endian::Little)).with_max_frame_len(4);
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        assert_eq!(
+            codec.encode(b"hello", &mut buffer),
+            Err(FrameError::TooLarge { len: 5 })
+        );
+        assert_eq!(
+            FrameCodec::new(LengthPrefix::U8).encode(&[0; 256], &mut buffer),
+            Err(FrameError::TooLarge { len: 256 })
+        );
+        assert_eq!(
+            codec.encode(b"hey", &mut Buffer::<[u8; 6]>::new()),
+            Err(FrameError::Write(WriteBufferError::BufferFull))
+        );
+
+        buffer.write(&[9, 0, 0, 0]);
+        assert_eq!(
+            codec.decode(&mut buffer),
+            Err(FrameError::TooLarge { len: 9 })
+        );
+        assert_eq!(buffer.remaining(), 4);
+    }
+}
@@ -0,0 +1,476 @@
+use core::{
+    alloc::Layout,
+    fmt::Debug,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut, NonNull},
+};
+use std::alloc::Allocator;
+
+use crate::{
+    buffer::ALLOC, unsafe_wild_copy, Buf, BufIndex, Buffer, Chunk, ChunkBuilder, LenUint, ReadBuf,
+    ReadBufMut, WriteBuf, WriteBufferError,
+};
+
+/// Buffer whose capacity is chosen at runtime and grows through the allocator `A`.
+///
+/// [`WriteBuf::write`] reallocates when the data doesn't fit, while [`WriteBuf::try_write`]
+/// and [`WriteBuf::try_write_fast`] still fail with [`WriteBufferError::BufferFull`].
+///
+/// Both positions are stored as `L`, so the capacity can't grow past [`BufIndex::MAX`].
+pub type GrowableBuffer<T, A = ALLOC, L = LenUint> =
+    Buffer<GrowableChunk<T, A>, A, GrowableChunk<T, A>, L>;
+
+const MIN_NON_ZERO_CAPACITY: usize = 8;
+
+/// Heap allocated chunk with a runtime capacity, tracking its initialized prefix
+/// like [`UninitChunk`](crate::UninitChunk).
+pub struct GrowableChunk<T, A: Allocator = ALLOC> {
+    ptr: NonNull<T>,
+    capacity: usize,
+    init: usize,
+    alloc: A,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for GrowableChunk<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for GrowableChunk<T, A> {}
+
+impl<T, A: Allocator> GrowableChunk<T, A> {
+    #[inline(always)]
+    pub const fn new_in(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            capacity: 0,
+            init: 0,
+            alloc,
+        }
+    }
+
+    #[inline(always)]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut chunk = Self::new_in(alloc);
+        chunk.grow_to(capacity);
+        chunk
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Length of the initialized prefix.
+    #[inline(always)]
+    pub const fn init_len(&self) -> usize {
+        self.init
+    }
+
+    #[inline(always)]
+    pub const fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Reallocates so that at least `capacity` elements fit, keeping the existing elements in place.
+    pub fn grow_to(&mut self, capacity: usize) {
+        if capacity <= self.capacity {
+            return;
+        }
+        let new_layout = Layout::array::<T>(capacity).expect("capacity overflow");
+        if new_layout.size() != 0 {
+            let old_layout = Layout::array::<T>(self.capacity).unwrap();
+            let result = if old_layout.size() == 0 {
+                self.alloc.allocate(new_layout)
+            } else {
+                unsafe { self.alloc.grow(self.ptr.cast(), old_layout, new_layout) }
+            };
+            match result {
+                Ok(ptr) => self.ptr = ptr.cast(),
+                #[cfg(feature = "std")]
+                Err(_) => std::alloc::handle_alloc_error(new_layout),
+                #[cfg(not(feature = "std"))]
+                Err(_) => panic!("allocation failed"),
+            }
+        }
+        self.capacity = capacity;
+    }
+}
+
+impl<T, A: Allocator> Drop for GrowableChunk<T, A> {
+    fn drop(&mut self) {
+        let layout = Layout::array::<T>(self.capacity).unwrap();
+        if layout.size() != 0 {
+            unsafe { self.alloc.deallocate(self.ptr.cast(), layout) };
+        }
+    }
+}
+
+impl<T, A: Allocator + Default> ChunkBuilder<A> for GrowableChunk<T, A> {
+    #[inline(always)]
+    fn new_in(alloc: A) -> Self {
+        GrowableChunk::new_in(alloc)
+    }
+
+    #[inline(always)]
+    fn new() -> Self {
+        GrowableChunk::new_in(A::default())
+    }
+
+    #[inline(always)]
+    fn new_zeroed() -> Self {
+        GrowableChunk::new_in(A::default())
+    }
+}
+
+impl<T, A: Allocator> Chunk<T> for GrowableChunk<T, A> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        unsafe { &*slice_from_raw_parts(self.ptr.as_ptr(), self.init) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { &mut *slice_from_raw_parts_mut(self.ptr.as_ptr(), self.init) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    #[inline(always)]
+    unsafe fn set_init(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity);
+        if len > self.init {
+            self.init = len;
+        }
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        assert!(capacity <= L::MAX, "capacity overflow");
+        Self::from_chunk(GrowableChunk::with_capacity_in(capacity, alloc))
+    }
+
+    #[inline(always)]
+    fn from_chunk(chunk: GrowableChunk<T, A>) -> Self {
+        Self {
+            chunk,
+            filled_pos: L::from_usize(0),
+            pos: L::from_usize(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes room for at least `additional` more elements after `filled_pos`.
+    ///
+    /// Panics when that takes more than [`BufIndex::MAX`] elements.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.filled_pos.to_usize() + additional;
+        if required > self.chunk.capacity() {
+            assert!(required <= L::MAX, "capacity overflow");
+            let doubled = self.chunk.capacity() * 2;
+            let capacity = if required > doubled { required } else { doubled };
+            let capacity = if capacity < MIN_NON_ZERO_CAPACITY {
+                MIN_NON_ZERO_CAPACITY
+            } else {
+                capacity
+            };
+            self.chunk.grow_to(core::cmp::min(capacity, L::MAX));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, L: BufIndex> GrowableBuffer<T, std::alloc::Global, L> {
+    #[inline(always)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, std::alloc::Global)
+    }
+}
+
+impl<T, A: Allocator + Default, L: BufIndex> ChunkBuilder<A> for GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    fn new_in(alloc: A) -> Self {
+        Self::from_chunk(GrowableChunk::new_in(alloc))
+    }
+
+    #[inline(always)]
+    fn new() -> Self {
+        Self::from_chunk(ChunkBuilder::new())
+    }
+
+    #[inline(always)]
+    fn new_zeroed() -> Self {
+        Self::from_chunk(ChunkBuilder::new_zeroed())
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> Chunk<T> for GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        unsafe { self.get_continuous(self.remaining()) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { self.get_continuous_mut(self.remaining()) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.chunk.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.chunk.as_mut_ptr()
+    }
+}
+
+impl<T: Copy, A: Allocator, L: BufIndex> Buf<T> for GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    fn clear(&mut self) {
+        self.filled_pos = L::from_usize(0);
+        self.pos = L::from_usize(0);
+    }
+
+    #[inline(always)]
+    fn compact(&mut self) {
+        let pos = self.pos.to_usize();
+        let len = self.filled_pos.to_usize() - pos;
+        if pos != 0 {
+            let ptr = self.chunk.as_mut_ptr();
+            unsafe { ptr.wrapping_add(pos).copy_to(ptr, len) };
+            self.filled_pos = L::from_usize(len);
+            self.pos = L::from_usize(0);
+        }
+    }
+}
+
+impl<T: Copy, A: Allocator, L: BufIndex> WriteBuf<T> for GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    fn write(&mut self, data: &[T]) {
+        self.reserve(data.len());
+        let filled_pos = self.filled_pos.to_usize();
+        let new_filled_pos = filled_pos + data.len();
+        let dst = self.chunk.as_mut_ptr().wrapping_add(filled_pos);
+        unsafe {
+            data.as_ptr().copy_to_nonoverlapping(dst, data.len());
+            self.chunk.set_init(new_filled_pos);
+        }
+        self.filled_pos = L::from_usize(new_filled_pos);
+    }
+
+    #[inline(always)]
+    fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
+        let filled_pos = self.filled_pos.to_usize();
+        let new_filled_pos = filled_pos + data.len();
+        if new_filled_pos <= self.chunk.capacity() {
+            self.filled_pos = L::from_usize(new_filled_pos);
+            let dst = self.chunk.as_mut_ptr().wrapping_add(filled_pos);
+            unsafe {
+                data.as_ptr().copy_to_nonoverlapping(dst, data.len());
+                self.chunk.set_init(new_filled_pos);
+            }
+            Ok(())
+        } else {
+            Err(WriteBufferError::BufferFull)
+        }
+    }
+
+    #[inline(always)]
+    fn try_write_fast<const LEN: usize>(&mut self, data: &[T; LEN]) -> Result<(), WriteBufferError> {
+        let filled_pos = self.filled_pos.to_usize();
+        let new_filled_pos = filled_pos + LEN;
+        if new_filled_pos <= self.chunk.capacity() {
+            let src_ptr = data.as_ptr();
+            let dst_ptr = self.chunk.as_mut_ptr().wrapping_add(filled_pos);
+            self.filled_pos = L::from_usize(new_filled_pos);
+            unsafe {
+                unsafe_wild_copy!([T; LEN], src_ptr, dst_ptr, LEN);
+                self.chunk.set_init(new_filled_pos);
+            }
+            Ok(())
+        } else {
+            Err(WriteBufferError::BufferFull)
+        }
+    }
+
+    #[inline(always)]
+    fn remaining_space(&self) -> usize {
+        self.chunk.capacity() - self.filled_pos.to_usize()
+    }
+
+    #[inline(always)]
+    fn filled_pos(&self) -> usize {
+        self.filled_pos.to_usize()
+    }
+
+    #[inline(always)]
+    unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+        self.filled_pos = L::from_usize(filled_pos);
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.chunk.capacity()
+    }
+
+    /// Doesn't grow, [`Buffer::reserve`] beforehand.
+    #[inline(always)]
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let filled_pos = self.filled_pos.to_usize();
+        let ptr = self.chunk.as_mut_ptr().wrapping_add(filled_pos) as *mut MaybeUninit<T>;
+        unsafe { &mut *slice_from_raw_parts_mut(ptr, self.chunk.capacity() - filled_pos) }
+    }
+
+    #[inline(always)]
+    unsafe fn commit(&mut self, len: usize) {
+        debug_assert!(len <= self.remaining_space());
+        let filled_pos = self.filled_pos.to_usize() + len;
+        self.filled_pos = L::from_usize(filled_pos);
+        unsafe { self.chunk.set_init(filled_pos) };
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> ReadBuf<T> for GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[T] {
+        let pos = self.pos.to_usize();
+        let slice_len = core::cmp::min(len, self.filled_pos.to_usize() - pos);
+        self.pos = L::from_usize(pos + slice_len);
+        unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(pos), slice_len) }
+    }
+
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[T] {
+        let pos = self.pos.to_usize();
+        let slice_len = core::cmp::min(len, self.filled_pos.to_usize() - pos);
+        unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(pos), slice_len) }
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.filled_pos.to_usize() - self.pos.to_usize()
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        let pos = self.pos.to_usize();
+        self.pos = L::from_usize(core::cmp::min(self.filled_pos.to_usize(), pos + len));
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos.to_usize()
+    }
+
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        self.pos = L::from_usize(pos);
+    }
+}
+
+impl<T, A: Allocator, L: BufIndex> ReadBufMut<T> for GrowableBuffer<T, A, L> {
+    #[inline(always)]
+    unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+        let pos = self.pos.to_usize();
+        let slice_len = core::cmp::min(len, self.filled_pos.to_usize() - pos);
+        unsafe {
+            &mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(pos), slice_len)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Allocator, L: BufIndex> std::io::Write for GrowableBuffer<u8, A, L> {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        WriteBuf::write(self, buf);
+        Ok(buf.len())
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: Debug, A: Allocator, L: BufIndex> Debug for GrowableBuffer<T, A, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.chunk.as_slice()[self.pos.to_usize()..self.filled_pos.to_usize()].fmt(f)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_grows() {
+        let mut buffer = GrowableBuffer::<u8>::with_capacity(4);
+        assert_eq!(buffer.capacity(), 4);
+        buffer.write(b"hello");
+        assert!(buffer.capacity() >= 5);
+        buffer.write(&[b'!'; 100]);
+        assert_eq!(buffer.remaining(), 105);
+        assert_eq!(buffer.read(5), b"hello");
+        assert_eq!(buffer.read(100), &[b'!'; 100]);
+    }
+
+    #[test]
+    fn test_try_write_full() {
+        let mut buffer = GrowableBuffer::<u8>::with_capacity_in(8, std::alloc::Global);
+        buffer.try_write(b"hello").unwrap();
+        assert!(buffer.try_write(b"world").is_err());
+        assert!(buffer.try_write_fast(b"world").is_err());
+        buffer.try_write_fast(b"!!!").unwrap();
+        assert_eq!(buffer.remaining_space(), 0);
+        assert_eq!(format!("{:?}", buffer), format!("{:?}", b"hello!!!"));
+    }
+
+    #[test]
+    fn test_empty_buffer() {
+        let mut buffer: GrowableBuffer<u8> = ChunkBuilder::new();
+        assert_eq!(buffer.capacity(), 0);
+        assert!(buffer.try_write(b"a").is_err());
+        buffer.write(b"a");
+        assert_eq!(buffer.read(1), b"a");
+    }
+
+    #[test]
+    fn test_growth_capped_by_index() {
+        let mut buffer = GrowableBuffer::<u8, std::alloc::Global, u8>::with_capacity(200);
+        buffer.write(&[1; 250]);
+        assert_eq!(buffer.capacity(), 255);
+        buffer.write(&[2; 5]);
+        assert!(buffer.try_write(&[3]).is_err());
+        assert_eq!(buffer.read(255)[250..], [2; 5]);
+
+        let mut buffer = GrowableBuffer::<u8, std::alloc::Global, usize>::with_capacity(0);
+        buffer.write(&[1; 0x20000]);
+        assert_eq!(buffer.remaining(), 0x20000);
+    }
+
+    #[test]
+    fn test_chunk_covers_written_elements() {
+        let mut buffer = GrowableBuffer::<u8>::with_capacity(16);
+        assert_eq!(buffer.chunk.as_slice(), &[]);
+        buffer.write(b"abc");
+        buffer.try_write_fast(b"de").unwrap();
+        let spare = buffer.spare_capacity_mut();
+        spare[0].write(b'f');
+        unsafe { buffer.commit(1) };
+        assert_eq!(buffer.chunk.init_len(), 6);
+        buffer.clear();
+        buffer.write(b"x");
+        assert_eq!(buffer.chunk.as_slice(), b"xbcdef");
+    }
+}
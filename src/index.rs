@@ -0,0 +1,86 @@
+use crate::{declare_const_impl, declare_const_trait};
+
+declare_const_trait! {
+    /// Integer type that stores `pos` and `filled_pos` of a [`Buffer`](crate::Buffer) or
+    /// [`RingBuffer`](crate::RingBuffer).
+    ///
+    /// Building a buffer whose positions don't fit the index type fails to compile, so `u8`
+    /// shrinks tiny buffers and `usize` lifts the limit of [`LenUint`](crate::LenUint).
+    pub trait BufIndex<()>: const (), (Copy) {
+        const MAX: usize;
+        /// Truncates `value`, which the buffer keeps within [`BufIndex::MAX`].
+        fn from_usize(value: usize) -> Self;
+        fn to_usize(self) -> usize;
+    }
+}
+
+macro_rules! impl_buf_index {
+    ($($ty:ty),*) => {
+        $(
+            declare_const_impl! {
+                (impl BufIndex for $ty),
+                (impl const BufIndex for $ty) {
+                    const MAX: usize = <$ty>::MAX as usize;
+
+                    #[inline(always)]
+                    fn from_usize(value: usize) -> Self {
+                        value as $ty
+                    }
+
+                    #[inline(always)]
+                    fn to_usize(self) -> usize {
+                        self as usize
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_buf_index!(u8, u16, u32, usize);
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::{buffer::ALLOC, Buf, Buffer, ReadBuf, RingBuffer, WriteBuf};
+
+    #[test]
+    fn test_u8_index() {
+        let mut buffer: Buffer<[u8; 255], ALLOC, [u8; 255], u8> = Buffer::new();
+        assert_eq!(size_of_val(&buffer), 255 + 2);
+        buffer.write(&[1; 200]);
+        buffer.write(&[2; 55]);
+        assert_eq!(
+            buffer.try_write(&[3]),
+            Err(crate::WriteBufferError::BufferFull)
+        );
+        assert_eq!(buffer.read(200), &[1; 200]);
+        buffer.compact();
+        assert_eq!(buffer.remaining(), 55);
+        assert_eq!(buffer.filled_pos(), 55);
+    }
+
+    #[test]
+    fn test_ring_u8_index_wraps() {
+        let mut ring: RingBuffer<[u8; 128], ALLOC, [u8; 128], u8> = RingBuffer::new();
+        for i in 0..10u8 {
+            ring.write(&[i; 100]);
+            let (first, second) = ring.as_slices();
+            assert_eq!([first, second].concat(), [i; 100]);
+            ring.advance(100);
+        }
+        ring.write(&[7; 128]);
+        assert_eq!(ring.remaining(), 128);
+        assert_eq!(ring.remaining_space(), 0);
+    }
+
+    #[test]
+    fn test_usize_index() {
+        let mut buffer: Buffer<[u8; 16], ALLOC, [u8; 16], usize> = Buffer::new();
+        assert_eq!(size_of_val(&buffer), 16 + 2 * size_of::<usize>());
+        buffer.write(b"hello");
+        buffer.advance(8);
+        assert_eq!(buffer.pos(), 5);
+        assert_eq!(buffer.remaining_space(), 11);
+    }
+}
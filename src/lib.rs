@@ -6,6 +6,7 @@
 #![feature(const_copy_from_slice)]
 #![feature(const_trait_impl)]
 #![cfg_attr(feature = "std", feature(new_zeroed_alloc))]
+#![cfg_attr(feature = "std", feature(core_io_borrowed_buf, read_buf))]
 #![feature(allocator_api)]
 #![cfg_attr(test, feature(test))]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -25,7 +26,87 @@ pub use traits::*;
 mod buffer;
 pub use buffer::*;
 
+mod index;
+pub use index::*;
+
+mod ring;
+pub use ring::*;
+
+mod compact;
+pub use compact::*;
+
+mod growable;
+pub use growable::*;
+
+mod borrowed;
+pub use borrowed::*;
+
+mod slice;
+pub use slice::*;
+
+mod primitive;
+pub use primitive::*;
+
+mod varint;
+pub use varint::*;
+
+mod checkpoint;
+pub use checkpoint::*;
+
+mod take;
+pub use take::*;
+
+mod encode;
+pub use encode::*;
+#[cfg(feature = "derive")]
+pub use fastbuf_derive::{Decode, Encode};
+
+mod frame;
+pub use frame::*;
+
+mod delimited;
+pub use delimited::*;
+
+#[cfg(feature = "std")]
+mod spsc;
+#[cfg(feature = "std")]
+pub use spsc::*;
+
+#[cfg(feature = "std")]
+mod vectored;
+#[cfg(feature = "std")]
+pub use vectored::*;
+
+#[cfg(feature = "std")]
+mod chain;
+#[cfg(feature = "std")]
+pub use chain::*;
+
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+pub use pool::*;
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+mod mirrored;
+#[cfg(all(target_os = "linux", feature = "std"))]
+pub use mirrored::*;
+
+#[cfg(feature = "bytes")]
+mod bytes_compat;
+
+#[cfg(feature = "serde")]
+mod serde_compat;
+#[cfg(feature = "serde")]
+pub use serde_compat::*;
+
+#[cfg(feature = "tokio")]
+mod tokio_compat;
+#[cfg(feature = "tokio")]
+pub use tokio_compat::*;
+
 mod chunk;
+pub use chunk::UninitChunk;
 
 #[cfg(not(feature = "std"))]
 pub(crate) struct EmptyAlloc;
@@ -60,14 +141,16 @@ pub(crate) mod macros {
 
     #[macro_export]
     macro_rules! declare_const_trait {
-        ($visibility:vis trait $name:ident<($($generics:tt)*)>
+        ($(#[$($attrs:tt)*])* $visibility:vis trait $name:ident<($($generics:tt)*)>
          : const ($($const_supertrait:path),*), ($($supertrait:path),*) {$($body:tt)*}) => {
             #[cfg(not(feature = "const-trait"))]
+            $(#[$($attrs)*])*
             $visibility trait $name<$($generics)*>: $($const_supertrait +)* $($supertrait + )* {
                 $($body)*
             }
 
             #[cfg(feature = "const-trait")]
+            $(#[$($attrs)*])*
             #[const_trait]
             $visibility trait $name<$($generics)*>: $(const $const_supertrait +)* $($supertrait + )* {
                 $($body)*
@@ -0,0 +1,438 @@
+use core::{
+    fmt::Debug,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::{null_mut, slice_from_raw_parts, slice_from_raw_parts_mut, NonNull},
+};
+use std::{alloc::Allocator, io};
+
+use crate::{
+    buffer::ALLOC, const_min, unsafe_wild_copy, Buf, BufIndex, Buffer, Chunk, ChunkBuilder,
+    LenUint, ReadBuf, ReadBufMut, WriteBuf, WriteBufferError,
+};
+
+/// Ring buffer over a [`MirroredChunk`], every readable or writable region is one continuous slice.
+///
+/// Positions count modulo twice the capacity, like [`RingBuffer`](crate::RingBuffer),
+/// but [`ReadBuf::read`] and [`ReadBuf::get_continuous`] never stop at the end of the chunk.
+///
+/// Both positions are stored as `L`, which has to hold positions up to `2 * capacity - 1`.
+pub type MirroredBuffer<const N: usize, A = ALLOC, L = LenUint> =
+    Buffer<MirroredChunk<N>, A, MirroredChunk<N>, L>;
+
+/// At least `N` bytes from a `memfd_create` file mapped twice back to back.
+///
+/// The capacity is `N` rounded up to the page size, byte `i + capacity` is the same memory as byte `i`.
+pub struct MirroredChunk<const N: usize> {
+    ptr: NonNull<u8>,
+    capacity: usize,
+}
+
+unsafe impl<const N: usize> Send for MirroredChunk<N> {}
+unsafe impl<const N: usize> Sync for MirroredChunk<N> {}
+
+impl<const N: usize> MirroredChunk<N> {
+    pub fn try_new() -> io::Result<Self> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let capacity = N.max(1).next_multiple_of(page_size);
+        unsafe {
+            let fd = libc::memfd_create(c"fastbuf".as_ptr(), libc::MFD_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let result = Self::map(fd, capacity);
+            libc::close(fd);
+            result
+        }
+    }
+
+    unsafe fn map(fd: libc::c_int, capacity: usize) -> io::Result<Self> {
+        unsafe {
+            if libc::ftruncate(fd, capacity as libc::off_t) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let base = libc::mmap(
+                null_mut(),
+                capacity * 2,
+                libc::PROT_NONE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+            if base == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            for offset in [0, capacity] {
+                let ptr = libc::mmap(
+                    base.byte_add(offset),
+                    capacity,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED | libc::MAP_FIXED,
+                    fd,
+                    0,
+                );
+                if ptr == libc::MAP_FAILED {
+                    let err = io::Error::last_os_error();
+                    libc::munmap(base, capacity * 2);
+                    return Err(err);
+                }
+            }
+            Ok(Self {
+                ptr: NonNull::new_unchecked(base as *mut u8),
+                capacity,
+            })
+        }
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<const N: usize> Drop for MirroredChunk<N> {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.capacity * 2) };
+    }
+}
+
+impl<const N: usize, A: Allocator> ChunkBuilder<A> for MirroredChunk<N> {
+    /// Panics when the mapping fails, use [`MirroredChunk::try_new`] to handle the error.
+    #[inline(always)]
+    fn new_in(_alloc: A) -> Self {
+        Self::try_new().expect("failed to map mirrored chunk")
+    }
+
+    #[inline(always)]
+    fn new() -> Self {
+        Self::try_new().expect("failed to map mirrored chunk")
+    }
+
+    /// Same as `new`, a fresh `memfd_create` file is already zeroed.
+    #[inline(always)]
+    fn new_zeroed() -> Self {
+        Self::try_new().expect("failed to map mirrored chunk")
+    }
+}
+
+impl<const N: usize> Chunk<u8> for MirroredChunk<N> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        unsafe { &*slice_from_raw_parts(self.ptr.as_ptr(), self.capacity) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { &mut *slice_from_raw_parts_mut(self.ptr.as_ptr(), self.capacity) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    pub fn try_new() -> io::Result<Self> {
+        Ok(Self::from_chunk(MirroredChunk::try_new()?))
+    }
+
+    #[inline(always)]
+    fn from_chunk(chunk: MirroredChunk<N>) -> Self {
+        assert!(chunk.capacity() <= L::MAX / 2 + 1, "capacity overflow");
+        Self {
+            chunk,
+            filled_pos: L::from_usize(0),
+            pos: L::from_usize(0),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    const fn wrap_pos(&self, pos: usize) -> usize {
+        if pos >= 2 * self.chunk.capacity {
+            pos - 2 * self.chunk.capacity
+        } else {
+            pos
+        }
+    }
+
+    #[inline(always)]
+    const fn physical(&self, pos: usize) -> usize {
+        if pos >= self.chunk.capacity {
+            pos - self.chunk.capacity
+        } else {
+            pos
+        }
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        let pos = self.pos.to_usize();
+        let filled_pos = self.filled_pos.to_usize();
+        if filled_pos >= pos {
+            filled_pos - pos
+        } else {
+            filled_pos + 2 * self.chunk.capacity - pos
+        }
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> ChunkBuilder<A> for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    fn new_in(alloc: A) -> Self {
+        Self::from_chunk(ChunkBuilder::new_in(alloc))
+    }
+
+    #[inline(always)]
+    fn new() -> Self {
+        Self::from_chunk(<MirroredChunk<N> as ChunkBuilder<A>>::new())
+    }
+
+    #[inline(always)]
+    fn new_zeroed() -> Self {
+        Self::from_chunk(<MirroredChunk<N> as ChunkBuilder<A>>::new_zeroed())
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> Chunk<u8> for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        unsafe { self.get_continuous(self.remaining()) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { self.get_continuous_mut(self.remaining()) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const u8 {
+        self.chunk.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.chunk.as_mut_ptr()
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> Buf<u8> for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    fn clear(&mut self) {
+        self.filled_pos = L::from_usize(0);
+        self.pos = L::from_usize(0);
+    }
+
+    #[inline(always)]
+    fn compact(&mut self) {
+        let len = self.len();
+        let index = self.physical(self.pos.to_usize());
+        if index != 0 {
+            self.chunk.as_mut_slice().rotate_left(index);
+        }
+        self.pos = L::from_usize(0);
+        self.filled_pos = L::from_usize(len);
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> WriteBuf<u8> for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    fn write(&mut self, data: &[u8]) {
+        self.try_write(data).expect("ring buffer overflow")
+    }
+
+    #[inline(always)]
+    fn try_write(&mut self, data: &[u8]) -> Result<(), WriteBufferError> {
+        if data.len() > self.remaining_space() {
+            return Err(WriteBufferError::BufferFull);
+        }
+        let filled_pos = self.filled_pos.to_usize();
+        let dst = self
+            .chunk
+            .as_mut_ptr()
+            .wrapping_add(self.physical(filled_pos));
+        unsafe { data.as_ptr().copy_to_nonoverlapping(dst, data.len()) };
+        self.filled_pos = L::from_usize(self.wrap_pos(filled_pos + data.len()));
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_write_fast<const LEN: usize>(
+        &mut self,
+        data: &[u8; LEN],
+    ) -> Result<(), WriteBufferError> {
+        if LEN > self.remaining_space() {
+            return Err(WriteBufferError::BufferFull);
+        }
+        let filled_pos = self.filled_pos.to_usize();
+        let src_ptr = data.as_ptr();
+        let dst_ptr = self
+            .chunk
+            .as_mut_ptr()
+            .wrapping_add(self.physical(filled_pos));
+        self.filled_pos = L::from_usize(self.wrap_pos(filled_pos + LEN));
+        unsafe {
+            unsafe_wild_copy!([u8; LEN], src_ptr, dst_ptr, LEN);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn remaining_space(&self) -> usize {
+        self.chunk.capacity() - self.len()
+    }
+
+    #[inline(always)]
+    fn filled_pos(&self) -> usize {
+        self.filled_pos.to_usize()
+    }
+
+    #[inline(always)]
+    unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+        self.filled_pos = L::from_usize(filled_pos);
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.chunk.capacity()
+    }
+
+    /// The whole remaining space, the second mapping makes it continuous.
+    #[inline(always)]
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        let len = self.remaining_space();
+        let index = self.physical(self.filled_pos.to_usize());
+        let ptr = self.chunk.as_mut_ptr().wrapping_add(index) as *mut MaybeUninit<u8>;
+        unsafe { &mut *slice_from_raw_parts_mut(ptr, len) }
+    }
+
+    #[inline(always)]
+    unsafe fn commit(&mut self, len: usize) {
+        debug_assert!(len <= self.remaining_space());
+        self.filled_pos = L::from_usize(self.wrap_pos(self.filled_pos.to_usize() + len));
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> ReadBuf<u8> for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[u8] {
+        let pos = self.pos.to_usize();
+        let slice_len = const_min!(len, self.len());
+        self.pos = L::from_usize(self.wrap_pos(pos + slice_len));
+        let ptr = self.chunk.as_ptr().wrapping_add(self.physical(pos));
+        unsafe { &*slice_from_raw_parts(ptr, slice_len) }
+    }
+
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[u8] {
+        let slice_len = const_min!(len, self.len());
+        let ptr = self
+            .chunk
+            .as_ptr()
+            .wrapping_add(self.physical(self.pos.to_usize()));
+        unsafe { &*slice_from_raw_parts(ptr, slice_len) }
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        let len = const_min!(len, self.len());
+        self.pos = L::from_usize(self.wrap_pos(self.pos.to_usize() + len));
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos.to_usize()
+    }
+
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        self.pos = L::from_usize(pos);
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> ReadBufMut<u8> for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [u8] {
+        let slice_len = const_min!(len, self.len());
+        let index = self.physical(self.pos.to_usize());
+        let ptr = self.chunk.as_mut_ptr().wrapping_add(index);
+        unsafe { &mut *slice_from_raw_parts_mut(ptr, slice_len) }
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> std::io::Write for MirroredBuffer<N, A, L> {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.try_write(buf)
+            .map_err(|_| std::io::Error::other("write buffer failed"))?;
+        Ok(buf.len())
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const N: usize, A: Allocator, L: BufIndex> Debug for MirroredBuffer<N, A, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_rounded_to_page() {
+        let buffer = MirroredBuffer::<100>::try_new().unwrap();
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        assert_eq!(buffer.capacity(), page_size);
+
+        let buffer = MirroredBuffer::<0x20000, ALLOC, usize>::try_new().unwrap();
+        assert_eq!(buffer.capacity(), 0x20000);
+    }
+
+    #[test]
+    fn test_continuous_across_wrap() {
+        let mut buffer: MirroredBuffer<4096> = ChunkBuilder::new();
+        let capacity = buffer.capacity();
+        buffer.write(&vec![0; capacity - 3]);
+        buffer.advance(capacity - 3);
+        buffer.write(b"hello");
+        assert_eq!(buffer.remaining_space(), capacity - 5);
+        assert_eq!(unsafe { buffer.get_continuous(5) }, b"hello");
+        assert_eq!(&buffer.chunk.as_slice()[..2], b"lo");
+        assert_eq!(format!("{:?}", buffer), format!("{:?}", b"hello"));
+
+        buffer.compact();
+        assert_eq!(buffer.pos(), 0);
+        assert_eq!(buffer.read(8), b"hello");
+    }
+
+    #[test]
+    fn test_full_ring() {
+        let mut buffer = MirroredBuffer::<4096>::try_new().unwrap();
+        let capacity = buffer.capacity();
+        buffer.write(b"abc");
+        buffer.advance(2);
+        let data: Vec<u8> = (0..capacity - 1).map(|i| i as u8).collect();
+        buffer.write(&data);
+        assert!(buffer.try_write_fast(b"x").is_err());
+        assert_eq!(buffer.read(1), b"c");
+        assert_eq!(buffer.read(capacity), &data[..]);
+    }
+}
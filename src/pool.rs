@@ -0,0 +1,407 @@
+use core::{
+    cell::RefCell,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::{
+    alloc::{Allocator, Global},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use crate::{buffer::ALLOC, Buffer};
+
+/// Buffer type handed out by the pools, same as [`BoxedBuffer`](crate::BoxedBuffer) when `A` is [`Global`].
+pub type PoolBuffer<S, A = ALLOC> = Buffer<S, A, Box<S, A>>;
+
+/// Snapshot of the counters kept by [`BufferPool`] and [`LocalBufferPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PoolStats {
+    /// Buffers allocated because the pool was empty.
+    pub allocations: usize,
+    /// Buffers handed out from the pool without allocating.
+    pub reuses: usize,
+    /// Buffers dropped on return because the pool was at its size cap.
+    pub discards: usize,
+    /// Buffers currently handed out.
+    pub in_use: usize,
+    /// Highest value `in_use` has reached.
+    pub high_water: usize,
+    /// Buffers currently waiting in the pool.
+    pub pooled: usize,
+}
+
+#[derive(Default)]
+struct Counters {
+    allocations: AtomicUsize,
+    reuses: AtomicUsize,
+    discards: AtomicUsize,
+    in_use: AtomicUsize,
+    high_water: AtomicUsize,
+}
+
+impl Counters {
+    #[inline(always)]
+    fn on_take(&self, reused: bool) {
+        if reused {
+            self.reuses.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+        }
+        let in_use = self.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+        self.high_water.fetch_max(in_use, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn snapshot(&self, pooled: usize) -> PoolStats {
+        PoolStats {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            reuses: self.reuses.load(Ordering::Relaxed),
+            discards: self.discards.load(Ordering::Relaxed),
+            in_use: self.in_use.load(Ordering::Relaxed),
+            high_water: self.high_water.load(Ordering::Relaxed),
+            pooled,
+        }
+    }
+}
+
+/// Zeroed like [`ChunkBuilder::new_in`](crate::ChunkBuilder::new_in) for boxed chunks.
+#[inline(always)]
+fn allocate<T: Copy, const N: usize, A: Allocator>(alloc: A) -> Box<[T; N], A> {
+    unsafe { Box::new_zeroed_in(alloc).assume_init() }
+}
+
+#[inline(always)]
+fn cleared<T, const N: usize, A: Allocator>(chunk: Box<[T; N], A>) -> PoolBuffer<[T; N], A> {
+    PoolBuffer::from_chunk(chunk)
+}
+
+struct SharedPool<S, A: Allocator> {
+    alloc: A,
+    max_pooled: usize,
+    free: Mutex<Vec<Box<S, A>>>,
+    counters: Counters,
+}
+
+impl<S, A: Allocator> SharedPool<S, A> {
+    #[inline(always)]
+    fn pop(&self) -> Option<Box<S, A>> {
+        self.free.lock().unwrap().pop()
+    }
+
+    /// Keeps `chunk` unless the pool is at its size cap.
+    #[inline(always)]
+    fn push(&self, chunk: Box<S, A>) -> bool {
+        let mut free = self.free.lock().unwrap();
+        if free.len() < self.max_pooled {
+            free.push(chunk);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Thread-safe pool of boxed buffers, cloning it shares the same pool.
+///
+/// Buffers are cleared before they are handed out and go back to the pool when the
+/// [`PooledBuffer`] is dropped. At most `max_pooled` idle buffers are kept.
+pub struct BufferPool<S, A: Allocator + Clone = ALLOC> {
+    shared: Arc<SharedPool<S, A>>,
+}
+
+impl<S, A: Allocator + Clone> Clone for BufferPool<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<S> BufferPool<S, Global> {
+    #[inline(always)]
+    pub fn new(max_pooled: usize) -> Self {
+        Self::new_in(max_pooled, Global)
+    }
+}
+
+impl<S, A: Allocator + Clone> BufferPool<S, A> {
+    #[inline(always)]
+    pub fn new_in(max_pooled: usize, alloc: A) -> Self {
+        Self {
+            shared: Arc::new(SharedPool {
+                alloc,
+                max_pooled,
+                free: Mutex::new(Vec::new()),
+                counters: Counters::default(),
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        let pooled = self.shared.free.lock().unwrap().len();
+        self.shared.counters.snapshot(pooled)
+    }
+}
+
+impl<T: Copy, const N: usize, A: Allocator + Clone> BufferPool<[T; N], A> {
+    pub fn take(&self) -> PooledBuffer<[T; N], A> {
+        let chunk = self.shared.pop();
+        self.shared.counters.on_take(chunk.is_some());
+        let chunk = chunk.unwrap_or_else(|| allocate(self.shared.alloc.clone()));
+        PooledBuffer {
+            buffer: ManuallyDrop::new(cleared(chunk)),
+            pool: self.clone(),
+        }
+    }
+}
+
+/// Buffer borrowed from a [`BufferPool`], returned to it on drop.
+pub struct PooledBuffer<S, A: Allocator + Clone = ALLOC> {
+    buffer: ManuallyDrop<PoolBuffer<S, A>>,
+    pool: BufferPool<S, A>,
+}
+
+impl<S, A: Allocator + Clone> Drop for PooledBuffer<S, A> {
+    fn drop(&mut self) {
+        let buffer = unsafe { ManuallyDrop::take(&mut self.buffer) };
+        let shared = &self.pool.shared;
+        shared.counters.in_use.fetch_sub(1, Ordering::Relaxed);
+        if !shared.push(buffer.chunk) {
+            shared.counters.discards.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<S, A: Allocator + Clone> Deref for PooledBuffer<S, A> {
+    type Target = PoolBuffer<S, A>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl<S, A: Allocator + Clone> DerefMut for PooledBuffer<S, A> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+struct LocalPool<S, A: Allocator + Clone> {
+    alloc: A,
+    max_pooled: usize,
+    free: RefCell<Vec<Box<S, A>>>,
+    fallback: Option<BufferPool<S, A>>,
+    counters: Counters,
+}
+
+/// Single-threaded pool meant to live in a `thread_local!`, with an optional [`BufferPool`] fallback.
+///
+/// Taking a buffer tries the local free list, then the fallback, then allocates.
+/// Returned buffers go to the local free list until it holds `max_pooled` buffers,
+/// then to the fallback.
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// use fastbuf::{LocalBufferPool, WriteBuf};
+///
+/// thread_local! {
+///     static POOL: LocalBufferPool<[u8; 4096]> = LocalBufferPool::new(16);
+/// }
+///
+/// let mut buffer = POOL.with(|pool| pool.take());
+/// buffer.write(b"hello");
+/// ```
+pub struct LocalBufferPool<S, A: Allocator + Clone = ALLOC> {
+    local: Rc<LocalPool<S, A>>,
+}
+
+impl<S, A: Allocator + Clone> Clone for LocalBufferPool<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            local: self.local.clone(),
+        }
+    }
+}
+
+impl<S> LocalBufferPool<S, Global> {
+    #[inline(always)]
+    pub fn new(max_pooled: usize) -> Self {
+        Self::new_in(max_pooled, Global)
+    }
+}
+
+impl<S, A: Allocator + Clone> LocalBufferPool<S, A> {
+    #[inline(always)]
+    pub fn new_in(max_pooled: usize, alloc: A) -> Self {
+        Self::from_parts(max_pooled, alloc, None)
+    }
+
+    /// Uses the allocator of `fallback` for new buffers.
+    #[inline(always)]
+    pub fn with_fallback(max_pooled: usize, fallback: BufferPool<S, A>) -> Self {
+        let alloc = fallback.shared.alloc.clone();
+        Self::from_parts(max_pooled, alloc, Some(fallback))
+    }
+
+    #[inline(always)]
+    fn from_parts(max_pooled: usize, alloc: A, fallback: Option<BufferPool<S, A>>) -> Self {
+        Self {
+            local: Rc::new(LocalPool {
+                alloc,
+                max_pooled,
+                free: RefCell::new(Vec::new()),
+                fallback,
+                counters: Counters::default(),
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.local.counters.snapshot(self.local.free.borrow().len())
+    }
+}
+
+impl<T: Copy, const N: usize, A: Allocator + Clone> LocalBufferPool<[T; N], A> {
+    pub fn take(&self) -> LocalPooledBuffer<[T; N], A> {
+        let local = &self.local;
+        let chunk = local.free.borrow_mut().pop().or_else(|| {
+            local
+                .fallback
+                .as_ref()
+                .and_then(|fallback| fallback.shared.pop())
+        });
+        local.counters.on_take(chunk.is_some());
+        let chunk = chunk.unwrap_or_else(|| allocate(local.alloc.clone()));
+        LocalPooledBuffer {
+            buffer: ManuallyDrop::new(cleared(chunk)),
+            pool: self.clone(),
+        }
+    }
+}
+
+/// Buffer borrowed from a [`LocalBufferPool`], returned to it on drop.
+pub struct LocalPooledBuffer<S, A: Allocator + Clone = ALLOC> {
+    buffer: ManuallyDrop<PoolBuffer<S, A>>,
+    pool: LocalBufferPool<S, A>,
+}
+
+impl<S, A: Allocator + Clone> Drop for LocalPooledBuffer<S, A> {
+    fn drop(&mut self) {
+        let buffer = unsafe { ManuallyDrop::take(&mut self.buffer) };
+        let local = &self.pool.local;
+        local.counters.in_use.fetch_sub(1, Ordering::Relaxed);
+        let mut free = local.free.borrow_mut();
+        let kept = if free.len() < local.max_pooled {
+            free.push(buffer.chunk);
+            true
+        } else {
+            match &local.fallback {
+                Some(fallback) => fallback.shared.push(buffer.chunk),
+                None => false,
+            }
+        };
+        if !kept {
+            local.counters.discards.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<S, A: Allocator + Clone> Deref for LocalPooledBuffer<S, A> {
+    type Target = PoolBuffer<S, A>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl<S, A: Allocator + Clone> DerefMut for LocalPooledBuffer<S, A> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{alloc::Layout, ptr::NonNull};
+    use std::alloc::AllocError;
+
+    use super::*;
+    use crate::{ReadBuf, WriteBuf};
+
+    #[test]
+    fn test_reuse_and_cap() {
+        let pool: BufferPool<[u8; 1024]> = BufferPool::new(1);
+        let mut first = pool.take();
+        first.write(b"hello");
+        let first_ptr = first.chunk.as_ptr();
+        let second = pool.take();
+        drop(first);
+        drop(second);
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                allocations: 2,
+                reuses: 0,
+                discards: 1,
+                in_use: 0,
+                high_water: 2,
+                pooled: 1,
+            }
+        );
+
+        let reused = pool.take();
+        assert_eq!(reused.chunk.as_ptr(), first_ptr);
+        assert_eq!(reused.remaining(), 0);
+        assert_eq!(pool.stats().reuses, 1);
+    }
+
+    #[test]
+    fn test_local_fallback() {
+        let global: BufferPool<[u8; 64]> = BufferPool::new(4);
+        let local = LocalBufferPool::with_fallback(1, global.clone());
+        let first = local.take();
+        let second = local.take();
+        drop(first);
+        drop(second);
+        assert_eq!(local.stats().pooled, 1);
+        assert_eq!(global.stats().pooled, 1);
+
+        let _first = local.take();
+        let _second = local.take();
+        let stats = local.stats();
+        assert_eq!((stats.allocations, stats.reuses, stats.pooled), (2, 2, 0));
+        assert_eq!(global.stats().pooled, 0);
+    }
+
+    #[derive(Clone, Copy)]
+    struct Counting(&'static AtomicUsize);
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    #[test]
+    fn test_custom_allocator() {
+        static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+        let pool = LocalBufferPool::<[u8; 256], _>::new_in(2, Counting(&ALLOCATIONS));
+        for _ in 0..4 {
+            let mut buffer = pool.take();
+            buffer.write(b"abc");
+            assert_eq!(buffer.read(3), b"abc");
+        }
+        assert_eq!(ALLOCATIONS.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{ReadBuf, ReadBufferError, WriteBuf, WriteBufferError};
+
+macro_rules! read_methods {
+    ($($ty:ty, $from_bytes:ident => $try_read:ident, $read:ident;)*) => {
+        $(
+            #[inline(always)]
+            fn $try_read(&mut self) -> Result<$ty, ReadBufferError> {
+                self.try_read_array().map(<$ty>::$from_bytes)
+            }
+
+            #[inline(always)]
+            fn $read(&mut self) -> $ty {
+                self.$try_read().expect("not enough data in buffer")
+            }
+        )*
+    };
+}
+
+macro_rules! write_methods {
+    ($($ty:ty, $to_bytes:ident => $try_write:ident, $write:ident;)*) => {
+        $(
+            #[inline(always)]
+            fn $try_write(&mut self, value: $ty) -> Result<(), WriteBufferError> {
+                self.try_write_fast(&value.$to_bytes())
+            }
+
+            #[inline(always)]
+            fn $write(&mut self, value: $ty) {
+                self.$try_write(value).expect("buffer full")
+            }
+        )*
+    };
+}
+
+/// Typed primitive readers for byte buffers, in big, little and native endian.
+pub trait ReadBufExt: ReadBuf<u8> {
+    /// Reads exactly `LEN` bytes, consuming nothing when fewer are available.
+    #[inline(always)]
+    fn try_read_array<const LEN: usize>(&mut self) -> Result<[u8; LEN], ReadBufferError> {
+        let slice = unsafe { self.get_continuous(LEN) };
+        if slice.len() == LEN {
+            let array = unsafe { (slice.as_ptr() as *const [u8; LEN]).read_unaligned() };
+            self.advance(LEN);
+            Ok(array)
+        } else if self.remaining() >= LEN {
+            let mut array = [0; LEN];
+            let mut read_len = 0;
+            while read_len < LEN {
+                let slice = self.read(LEN - read_len);
+                array[read_len..read_len + slice.len()].copy_from_slice(slice);
+                read_len += slice.len();
+            }
+            Ok(array)
+        } else {
+            Err(ReadBufferError::BufferUnderflow)
+        }
+    }
+
+    read_methods! {
+        u8, from_ne_bytes => try_read_u8, read_u8;
+        i8, from_ne_bytes => try_read_i8, read_i8;
+        u16, from_be_bytes => try_read_u16_be, read_u16_be;
+        u16, from_le_bytes => try_read_u16_le, read_u16_le;
+        u16, from_ne_bytes => try_read_u16_ne, read_u16_ne;
+        i16, from_be_bytes => try_read_i16_be, read_i16_be;
+        i16, from_le_bytes => try_read_i16_le, read_i16_le;
+        i16, from_ne_bytes => try_read_i16_ne, read_i16_ne;
+        u32, from_be_bytes => try_read_u32_be, read_u32_be;
+        u32, from_le_bytes => try_read_u32_le, read_u32_le;
+        u32, from_ne_bytes => try_read_u32_ne, read_u32_ne;
+        i32, from_be_bytes => try_read_i32_be, read_i32_be;
+        i32, from_le_bytes => try_read_i32_le, read_i32_le;
+        i32, from_ne_bytes => try_read_i32_ne, read_i32_ne;
+        u64, from_be_bytes => try_read_u64_be, read_u64_be;
+        u64, from_le_bytes => try_read_u64_le, read_u64_le;
+        u64, from_ne_bytes => try_read_u64_ne, read_u64_ne;
+        i64, from_be_bytes => try_read_i64_be, read_i64_be;
+        i64, from_le_bytes => try_read_i64_le, read_i64_le;
+        i64, from_ne_bytes => try_read_i64_ne, read_i64_ne;
+        u128, from_be_bytes => try_read_u128_be, read_u128_be;
+        u128, from_le_bytes => try_read_u128_le, read_u128_le;
+        u128, from_ne_bytes => try_read_u128_ne, read_u128_ne;
+        i128, from_be_bytes => try_read_i128_be, read_i128_be;
+        i128, from_le_bytes => try_read_i128_le, read_i128_le;
+        i128, from_ne_bytes => try_read_i128_ne, read_i128_ne;
+        f32, from_be_bytes => try_read_f32_be, read_f32_be;
+        f32, from_le_bytes => try_read_f32_le, read_f32_le;
+        f32, from_ne_bytes => try_read_f32_ne, read_f32_ne;
+        f64, from_be_bytes => try_read_f64_be, read_f64_be;
+        f64, from_le_bytes => try_read_f64_le, read_f64_le;
+        f64, from_ne_bytes => try_read_f64_ne, read_f64_ne;
+    }
+}
+
+impl<R: ReadBuf<u8> + ?Sized> ReadBufExt for R {}
+
+/// Typed primitive writers for byte buffers, in big, little and native endian.
+///
+/// Every write goes through [`WriteBuf::try_write_fast`] with the size of the primitive.
+pub trait WriteBufExt: WriteBuf<u8> {
+    write_methods! {
+        u8, to_ne_bytes => try_write_u8, write_u8;
+        i8, to_ne_bytes => try_write_i8, write_i8;
+        u16, to_be_bytes => try_write_u16_be, write_u16_be;
+        u16, to_le_bytes => try_write_u16_le, write_u16_le;
+        u16, to_ne_bytes => try_write_u16_ne, write_u16_ne;
+        i16, to_be_bytes => try_write_i16_be, write_i16_be;
+        i16, to_le_bytes => try_write_i16_le, write_i16_le;
+        i16, to_ne_bytes => try_write_i16_ne, write_i16_ne;
+        u32, to_be_bytes => try_write_u32_be, write_u32_be;
+        u32, to_le_bytes => try_write_u32_le, write_u32_le;
+        u32, to_ne_bytes => try_write_u32_ne, write_u32_ne;
+        i32, to_be_bytes => try_write_i32_be, write_i32_be;
+        i32, to_le_bytes => try_write_i32_le, write_i32_le;
+        i32, to_ne_bytes => try_write_i32_ne, write_i32_ne;
+        u64, to_be_bytes => try_write_u64_be, write_u64_be;
+        u64, to_le_bytes => try_write_u64_le, write_u64_le;
+        u64, to_ne_bytes => try_write_u64_ne, write_u64_ne;
+        i64, to_be_bytes => try_write_i64_be, write_i64_be;
+        i64, to_le_bytes => try_write_i64_le, write_i64_le;
+        i64, to_ne_bytes => try_write_i64_ne, write_i64_ne;
+        u128, to_be_bytes => try_write_u128_be, write_u128_be;
+        u128, to_le_bytes => try_write_u128_le, write_u128_le;
+        u128, to_ne_bytes => try_write_u128_ne, write_u128_ne;
+        i128, to_be_bytes => try_write_i128_be, write_i128_be;
+        i128, to_le_bytes => try_write_i128_le, write_i128_le;
+        i128, to_ne_bytes => try_write_i128_ne, write_i128_ne;
+        f32, to_be_bytes => try_write_f32_be, write_f32_be;
+        f32, to_le_bytes => try_write_f32_le, write_f32_le;
+        f32, to_ne_bytes => try_write_f32_ne, write_f32_ne;
+        f64, to_be_bytes => try_write_f64_be, write_f64_be;
+        f64, to_le_bytes => try_write_f64_le, write_f64_le;
+        f64, to_ne_bytes => try_write_f64_ne, write_f64_ne;
+    }
+}
+
+impl<W: WriteBuf<u8> + ?Sized> WriteBufExt for W {}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::{Buffer, RingBuffer};
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut buffer: Buffer<[u8; 64]> = Buffer::new();
+        buffer.write_u8(0xAB);
+        buffer.write_u16_be(0x0102);
+        buffer.write_i32_le(-2);
+        buffer.write_u64_ne(u64::MAX - 1);
+        buffer.write_f64_be(1.5);
+        assert_eq!(buffer.remaining(), 1 + 2 + 4 + 8 + 8);
+        assert_eq!(unsafe { buffer.get_continuous(3) }, &[0xAB, 0x01, 0x02]);
+
+        assert_eq!(buffer.read_u8(), 0xAB);
+        assert_eq!(buffer.read_u16_be(), 0x0102);
+        assert_eq!(buffer.read_i32_le(), -2);
+        assert_eq!(buffer.read_u64_ne(), u64::MAX - 1);
+        assert_eq!(buffer.read_f64_be(), 1.5);
+    }
+
+    #[test]
+    fn test_underflow_consumes_nothing() {
+        let mut buffer: Buffer<[u8; 8]> = Buffer::new();
+        buffer.write(&[1, 2, 3]);
+        assert_eq!(buffer.try_read_u32_be(), Err(ReadBufferError::BufferUnderflow));
+        assert_eq!(buffer.remaining(), 3);
+        assert_eq!(buffer.try_write_u64_le(0), Err(WriteBufferError::BufferFull));
+        assert_eq!(buffer.try_read_u16_le(), Ok(0x0201));
+    }
+
+    #[test]
+    fn test_read_across_ring_wrap() {
+        let mut buffer: RingBuffer<[u8; 8]> = RingBuffer::new();
+        buffer.write(&[0; 6]);
+        buffer.advance(6);
+        buffer.write_u32_be(0xDEADBEEF);
+        assert_eq!(buffer.as_slices(), (&[0xDE, 0xAD][..], &[0xBE, 0xEF][..]));
+        assert_eq!(buffer.read_u32_be(), 0xDEADBEEF);
+    }
+}
@@ -0,0 +1,547 @@
+use core::{fmt::Debug, marker::PhantomData, mem::MaybeUninit, ptr::slice_from_raw_parts};
+use std::{alloc::Allocator, ptr::slice_from_raw_parts_mut};
+
+use crate::{
+    buffer::ALLOC, const_min, declare_const_fn, declare_const_impl, unsafe_wild_copy, Buf,
+    BufIndex, Chunk, ChunkBuilder, LenUint, ReadBuf, ReadBufMut, WriteBuf, WriteBufferError,
+};
+
+#[cfg(feature = "std")]
+pub type BoxedRingBuffer<S, A = ALLOC> = RingBuffer<S, A, Box<S>>;
+
+/// Circular variant of [`Buffer`](crate::Buffer) where `pos` and `filled_pos` wrap around the chunk.
+///
+/// Positions count modulo `2 * N` so that a full ring can be told apart from an empty one.
+/// The element at position `pos` lives at index `pos % N` of the chunk.
+/// [`ReadBuf::read`] and [`ReadBuf::get_continuous`] never cross the end of the chunk,
+/// use [`RingBuffer::as_slices`] to see the whole readable region.
+///
+/// Both positions are stored as `L`, which has to hold positions up to `2 * N - 1`.
+pub struct RingBuffer<S, A: Allocator = ALLOC, C = S, L = LenUint> {
+    pub(crate) chunk: C,
+    pub(crate) filled_pos: L,
+    pub(crate) pos: L,
+    pub(crate) _marker: PhantomData<(A, S)>,
+}
+
+#[cfg(feature = "impl_copy_for_buffer")]
+impl<
+        T: Copy,
+        const N: usize,
+        A: Allocator,
+        C: ChunkBuilder<A> + Chunk<T> + Copy + Clone,
+        L: BufIndex,
+    > Copy for RingBuffer<[T; N], A, C, L>
+{
+}
+
+#[inline(always)]
+pub(crate) const fn wrap_pos<const N: usize>(pos: usize) -> usize {
+    if pos >= 2 * N {
+        pos - 2 * N
+    } else {
+        pos
+    }
+}
+
+#[inline(always)]
+pub(crate) const fn physical<const N: usize>(pos: usize) -> usize {
+    if pos >= N {
+        pos - N
+    } else {
+        pos
+    }
+}
+
+declare_const_impl! {
+    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> RingBuffer<[T; N], A, C, L>),
+    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> RingBuffer<[T; N], A, C, L>) {
+        declare_const_fn! {
+            #[inline(always)]
+            pub fn new_in(alloc: A) -> Self {
+                ChunkBuilder::new_in(alloc)
+            }
+        }
+
+        declare_const_fn! {
+            #[inline(always)]
+            pub fn new() -> Self {
+               ChunkBuilder::new()
+            }
+        }
+
+        declare_const_fn! {
+            #[inline(always)]
+            pub fn new_zeroed() -> Self {
+                ChunkBuilder::new_zeroed()
+            }
+        }
+
+        declare_const_fn! {
+            /// Returns the readable region as two slices, the second one is empty unless the data wraps.
+            #[inline(always)]
+            pub fn as_slices(&self) -> (&[T], &[T]) {
+                let (first_len, second_len) = self.readable_lens();
+                let ptr = self.chunk.as_ptr();
+                unsafe {
+                    (
+                        &*slice_from_raw_parts(ptr.wrapping_add(physical::<N>(self.pos.to_usize())), first_len),
+                        &*slice_from_raw_parts(ptr, second_len),
+                    )
+                }
+            }
+        }
+
+        declare_const_fn! {
+            /// Mutable version of [`RingBuffer::as_slices`].
+            #[inline(always)]
+            pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+                let (first_len, second_len) = self.readable_lens();
+                let ptr = self.chunk.as_mut_ptr();
+                unsafe {
+                    (
+                        &mut *slice_from_raw_parts_mut(ptr.wrapping_add(physical::<N>(self.pos.to_usize())), first_len),
+                        &mut *slice_from_raw_parts_mut(ptr, second_len),
+                    )
+                }
+            }
+        }
+
+        declare_const_fn! {
+            #[inline(always)]
+            fn readable_lens(&self) -> (usize, usize) {
+                let pos = self.pos.to_usize();
+                let filled_pos = self.filled_pos.to_usize();
+                let len = if filled_pos >= pos { filled_pos - pos } else { filled_pos + 2 * N - pos };
+                let first_len = const_min!(len, N - physical::<N>(pos));
+                (first_len, len - first_len)
+            }
+        }
+
+        declare_const_fn! {
+            /// Copies `data` to the end of the ring, callers check the remaining space beforehand.
+            #[inline(always)]
+            fn copy_in(&mut self, data: &[T]) {
+                let filled_pos = self.filled_pos.to_usize();
+                let index = physical::<N>(filled_pos);
+                let first_len = const_min!(data.len(), N - index);
+                let second_len = const_min!(data.len() - first_len, index);
+                let src = data.as_ptr();
+                let dst = self.chunk.as_mut_ptr();
+                unsafe {
+                    src.copy_to_nonoverlapping(dst.add(index), first_len);
+                    src.add(first_len).copy_to_nonoverlapping(dst, second_len);
+                    self.chunk.set_init(index + first_len);
+                }
+                self.filled_pos = L::from_usize(wrap_pos::<N>(filled_pos + first_len + second_len));
+            }
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> ChunkBuilder<A> for RingBuffer<[T; N], A, C, L>),
+    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const ChunkBuilder<A> for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        fn new_in(alloc: A) -> Self {
+            const { assert!(N <= L::MAX / 2 + 1, "capacity doesn't fit the index type") };
+            Self {
+                chunk: C::new_in(alloc),
+                filled_pos: L::from_usize(0),
+                pos: L::from_usize(0),
+                _marker: PhantomData,
+            }
+        }
+
+        #[inline(always)]
+        fn new() -> Self {
+            const { assert!(N <= L::MAX / 2 + 1, "capacity doesn't fit the index type") };
+            Self {
+                chunk: C::new(),
+                filled_pos: L::from_usize(0),
+                pos: L::from_usize(0),
+                _marker: PhantomData,
+            }
+        }
+
+        #[inline(always)]
+        fn new_zeroed() -> Self {
+            const { assert!(N <= L::MAX / 2 + 1, "capacity doesn't fit the index type") };
+            Self {
+                chunk: C::new_zeroed(),
+                filled_pos: L::from_usize(0),
+                pos: L::from_usize(0),
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Chunk<T> for RingBuffer<[T; N], A, C, L>),
+    (impl<T, A: Allocator, const N: usize, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const Chunk<T> for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        fn as_slice(&self) -> &[T] {
+            unsafe { self.get_continuous(N) }
+        }
+
+        #[inline(always)]
+        fn as_mut_slice(&mut self) -> &mut [T] {
+            unsafe { self.get_continuous_mut(N) }
+        }
+
+        #[inline(always)]
+        fn as_ptr(&self) -> *const T {
+            self.chunk.as_ptr()
+        }
+
+        #[inline(always)]
+        fn as_mut_ptr(&mut self) -> *mut T {
+            self.chunk.as_mut_ptr()
+        }
+    }
+}
+
+impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T> + Clone, L: BufIndex> Clone
+    for RingBuffer<[T; N], A, C, L>
+{
+    fn clone(&self) -> Self {
+        Self {
+            chunk: self.chunk.clone(),
+            filled_pos: self.filled_pos,
+            pos: self.pos,
+            _marker: PhantomData,
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Buf<T> for RingBuffer<[T; N], A, C, L>),
+    (impl<T: Copy, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const Buf<T> for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        fn clear(&mut self) {
+            self.filled_pos = L::from_usize(0);
+            self.pos = L::from_usize(0);
+        }
+
+        #[inline(always)]
+        fn compact(&mut self) {
+            let len = self.remaining();
+            let index = physical::<N>(self.pos.to_usize());
+            let ptr = self.chunk.as_mut_ptr();
+            if index + len <= N {
+                unsafe { ptr.wrapping_add(index).copy_to(ptr, len) };
+            } else {
+                // Wrapping around means the whole chunk was written to.
+                unsafe { (&mut *slice_from_raw_parts_mut(ptr as *mut MaybeUninit<T>, N)).rotate_left(index) };
+            }
+            self.pos = L::from_usize(0);
+            self.filled_pos = L::from_usize(len);
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T: Copy, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> WriteBuf<T> for RingBuffer<[T; N], A, C, L>),
+    (impl<T: Copy, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const WriteBuf<T> for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
+            if data.len() <= self.remaining_space() {
+                self.copy_in(data);
+                Ok(())
+            } else {
+                Err(WriteBufferError::BufferFull)
+            }
+        }
+
+        #[inline(always)]
+        fn try_write_fast<const LEN: usize>(&mut self, data: &[T; LEN]) -> Result<(), WriteBufferError> {
+            if LEN > self.remaining_space() {
+                return Err(WriteBufferError::BufferFull);
+            }
+            let filled_pos = self.filled_pos.to_usize();
+            let index = physical::<N>(filled_pos);
+            if index + LEN <= N {
+                let src_ptr = data.as_ptr();
+                let dst_ptr = self.chunk.as_mut_ptr().wrapping_add(index);
+                self.filled_pos = L::from_usize(wrap_pos::<N>(filled_pos + LEN));
+                unsafe {
+                    unsafe_wild_copy!([T; LEN], src_ptr, dst_ptr, LEN);
+                    self.chunk.set_init(index + LEN);
+                }
+            } else {
+                self.copy_in(data);
+            }
+            Ok(())
+        }
+
+        #[inline(always)]
+        fn write(&mut self, data: &[T]) {
+            assert!(data.len() <= self.remaining_space(), "ring buffer overflow");
+            self.copy_in(data);
+        }
+
+        #[inline(always)]
+        fn remaining_space(&self) -> usize {
+            N - self.remaining()
+        }
+
+        #[inline(always)]
+        fn filled_pos(&self) -> usize {
+            self.filled_pos.to_usize()
+        }
+
+        #[inline(always)]
+        unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+            self.filled_pos = L::from_usize(filled_pos);
+        }
+
+        #[inline(always)]
+        fn capacity(&self) -> usize {
+            N
+        }
+
+        #[inline(always)]
+        fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+            let index = physical::<N>(self.filled_pos.to_usize());
+            let len = const_min!(self.remaining_space(), N - index);
+            unsafe {
+                &mut *slice_from_raw_parts_mut(
+                    self.chunk.as_mut_ptr().wrapping_add(index) as *mut MaybeUninit<T>,
+                    len,
+                )
+            }
+        }
+
+        #[inline(always)]
+        unsafe fn commit(&mut self, len: usize) {
+            let filled_pos = self.filled_pos.to_usize();
+            debug_assert!(len <= self.remaining_space());
+            self.filled_pos = L::from_usize(wrap_pos::<N>(filled_pos + len));
+            unsafe { self.chunk.set_init(const_min!(physical::<N>(filled_pos) + len, N)) };
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> ReadBuf<T> for RingBuffer<[T; N], A, C, L>),
+    (impl<T, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const ReadBuf<T> for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        fn read(&mut self, len: usize) -> &[T] {
+            let pos = self.pos.to_usize();
+            let slice_len = const_min!(len, self.readable_lens().0);
+            self.pos = L::from_usize(wrap_pos::<N>(pos + slice_len));
+            unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(physical::<N>(pos)), slice_len) }
+        }
+
+        #[inline(always)]
+        unsafe fn get_continuous(&self, len: usize) -> &[T] {
+            let slice_len = const_min!(len, self.readable_lens().0);
+            let index = physical::<N>(self.pos.to_usize());
+            unsafe { &*slice_from_raw_parts(self.chunk.as_ptr().wrapping_add(index), slice_len) }
+        }
+
+        #[inline(always)]
+        fn remaining(&self) -> usize {
+            let (first_len, second_len) = self.readable_lens();
+            first_len + second_len
+        }
+
+        #[inline(always)]
+        fn advance(&mut self, len: usize) {
+            let len = const_min!(len, self.remaining());
+            self.pos = L::from_usize(wrap_pos::<N>(self.pos.to_usize() + len));
+        }
+
+        #[inline(always)]
+        fn pos(&self) -> usize {
+            self.pos.to_usize()
+        }
+
+        #[inline(always)]
+        unsafe fn set_pos(&mut self, pos: usize) {
+            self.pos = L::from_usize(pos);
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> ReadBufMut<T> for RingBuffer<[T; N], A, C, L>),
+    (impl<T, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> const ReadBufMut<T> for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+            let slice_len = const_min!(len, self.readable_lens().0);
+            let index = physical::<N>(self.pos.to_usize());
+            unsafe { &mut *slice_from_raw_parts_mut(self.chunk.as_mut_ptr().wrapping_add(index), slice_len) }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+declare_const_impl! {
+    (impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> std::io::Write for RingBuffer<[u8; N], A, C, L>),
+    (impl<const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<u8>, L: const BufIndex> std::io::Write for RingBuffer<[u8; N], A, C, L>) {
+        #[inline(always)]
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.try_write(buf)
+                .map_err(|_| std::io::Error::other("write buffer failed"))?;
+            Ok(buf.len())
+        }
+
+        #[inline(always)]
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Default for RingBuffer<[T; N], A, C, L>),
+    (impl<T, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> Default for RingBuffer<[T; N], A, C, L>) {
+        #[inline(always)]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+}
+
+declare_const_impl! {
+    (impl<T: Copy + Debug, const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex> Debug for RingBuffer<[T; N], A, C, L>),
+    (impl<T: Copy + Debug, const N: usize, A: Allocator, C: const ChunkBuilder<A> + const Chunk<T>, L: const BufIndex> Debug for RingBuffer<[T; N], A, C, L>) {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let (first, second) = self.as_slices();
+            f.debug_list().entries(first).entries(second).finish()
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_wraps_around() {
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+                buffer.write(b"hello world");
+                buffer.advance(6);
+                assert_eq!(buffer.remaining_space(), 11);
+
+                buffer.write(b"!!!!!!");
+                assert_eq!(buffer.remaining(), 11);
+                assert_eq!(buffer.as_slices(), (&b"world!!!!!"[..], &b"!"[..]));
+                assert_eq!(format!("{:?}", buffer), format!("{:?}", b"world!!!!!!"));
+            }
+        }
+
+        test!(RingBuffer::<[u8; 16]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedRingBuffer::<[u8; 16]>::new());
+    }
+
+    #[test]
+    fn test_read_stops_at_wrap_point() {
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+                buffer.write(b"abcdef");
+                buffer.advance(5);
+                buffer.write(b"ghij");
+
+                assert_eq!(buffer.read(5), b"fgh");
+                assert_eq!(buffer.read(5), b"ij");
+                assert_eq!(buffer.remaining(), 0);
+                assert_eq!(buffer.remaining_space(), 8);
+            }
+        }
+
+        test!(RingBuffer::<[u8; 8]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedRingBuffer::<[u8; 8]>::new());
+    }
+
+    #[test]
+    fn test_try_write_full() {
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+                buffer.write(b"abcdef");
+                buffer.advance(3);
+                buffer.try_write(b"ghijk").unwrap();
+                assert_eq!(buffer.remaining_space(), 0);
+                assert_eq!(buffer.remaining(), 8);
+                assert!(buffer.try_write(b"l").is_err());
+                assert!(buffer.try_write_fast(b"l").is_err());
+                assert_eq!(buffer.as_slices(), (&b"defgh"[..], &b"ijk"[..]));
+            }
+        }
+
+        test!(RingBuffer::<[u8; 8]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedRingBuffer::<[u8; 8]>::new());
+    }
+
+    #[test]
+    fn test_compact_unwraps() {
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+                buffer.write(b"abcdef");
+                buffer.advance(4);
+                buffer.write(b"ghij");
+                buffer.compact();
+                assert_eq!(buffer.as_slices(), (&b"efghij"[..], &b""[..]));
+                assert_eq!(buffer.remaining_space(), 2);
+            }
+        }
+
+        test!(RingBuffer::<[u8; 8]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedRingBuffer::<[u8; 8]>::new());
+    }
+
+    #[test]
+    fn test_try_write_fast_wraps_around() {
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+                buffer.try_write_fast(b"abcdef").unwrap();
+                buffer.advance(6);
+                buffer.try_write_fast(b"ghij").unwrap();
+                assert_eq!(buffer.as_slices(), (&b"gh"[..], &b"ij"[..]));
+                buffer.clear();
+                assert_eq!(buffer.remaining(), 0);
+                assert_eq!(buffer.remaining_space(), 8);
+            }
+        }
+
+        test!(RingBuffer::<[u8; 8]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedRingBuffer::<[u8; 8]>::new());
+    }
+
+    #[test]
+    fn test_read_to_buf_after_wrap() {
+        use crate::ReadToBuf;
+
+        macro_rules! test {
+            ($($buf:tt)*) => {
+                let mut buffer = $($buf)*;
+                buffer.write(&[0; 6]);
+                buffer.advance(6);
+                buffer.write(b"abcd");
+
+                let mut reader = &b"efghij"[..];
+                assert_eq!(reader.read_to_buf(&mut buffer).unwrap(), 4);
+                assert_eq!(buffer.as_slices(), (&b"ab"[..], &b"cdefgh"[..]));
+                assert_eq!(buffer.remaining_space(), 0);
+            }
+        }
+
+        test!(RingBuffer::<[u8; 8]>::new());
+        #[cfg(all(not(feature = "const-trait"), feature = "std"))]
+        test!(BoxedRingBuffer::<[u8; 8]>::new());
+    }
+}
@@ -0,0 +1,885 @@
+use core::fmt::Display;
+
+use serde::{
+    de::{self, DeserializeOwned, IntoDeserializer, Visitor},
+    ser, Deserialize, Serialize,
+};
+
+use crate::{
+    varint::decode_unsigned, zigzag_decode_64, ReadBuf, ReadBufCheckpoint, ReadBufExt,
+    ReadBufferError, ReadVarInt, VarIntError, WriteBuf, WriteBufCheckpoint, WriteBufExt,
+    WriteBufferError, WriteVarInt, MAX_VAR_U64_LEN,
+};
+
+/// Error of the compact binary serde format.
+#[derive(Debug)]
+pub enum SerdeError {
+    /// The buffer ran out of space, nothing was written.
+    Write(WriteBufferError),
+    /// The buffer ended in the middle of a value, nothing was consumed.
+    Read(ReadBufferError),
+    InvalidVarInt,
+    InvalidBool(u8),
+    InvalidChar(u32),
+    InvalidUtf8,
+    InvalidOptionTag(u8),
+    /// Sequences and maps need their length up front.
+    UnknownLength,
+    /// The format isn't self-describing, so `deserialize_any` can't be supported.
+    AnyNotSupported,
+    Custom(String),
+}
+
+impl Display for SerdeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SerdeError::Write(err) => write!(f, "write failed: {err:?}"),
+            SerdeError::Read(err) => write!(f, "read failed: {err:?}"),
+            SerdeError::InvalidVarInt => f.write_str("invalid varint"),
+            SerdeError::InvalidBool(byte) => write!(f, "invalid bool: {byte}"),
+            SerdeError::InvalidChar(value) => write!(f, "invalid char: {value:#x}"),
+            SerdeError::InvalidUtf8 => f.write_str("invalid utf-8"),
+            SerdeError::InvalidOptionTag(tag) => write!(f, "invalid option tag: {tag}"),
+            SerdeError::UnknownLength => f.write_str("sequence length must be known"),
+            SerdeError::AnyNotSupported => f.write_str("deserialize_any is not supported"),
+            SerdeError::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for SerdeError {}
+
+impl ser::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError::Custom(msg.to_string())
+    }
+}
+
+impl From<WriteBufferError> for SerdeError {
+    fn from(err: WriteBufferError) -> Self {
+        SerdeError::Write(err)
+    }
+}
+
+impl From<ReadBufferError> for SerdeError {
+    fn from(err: ReadBufferError) -> Self {
+        SerdeError::Read(err)
+    }
+}
+
+impl From<VarIntError> for SerdeError {
+    fn from(err: VarIntError) -> Self {
+        match err {
+            VarIntError::Incomplete => SerdeError::Read(ReadBufferError::BufferUnderflow),
+            VarIntError::Overlong => SerdeError::InvalidVarInt,
+        }
+    }
+}
+
+/// Serializes `value` after `filled_pos`, writing nothing when it doesn't fit.
+pub fn serialize_to_buf<T: Serialize + ?Sized>(
+    value: &T,
+    buf: &mut impl WriteBuf<u8>,
+) -> Result<(), SerdeError> {
+    buf.write_transaction(|buf| value.serialize(&mut BufSerializer::new(buf)))
+}
+
+/// Deserializes an owned value from the readable region, consuming nothing on error.
+///
+/// Works across the wrap point of a [`RingBuffer`](crate::RingBuffer).
+pub fn deserialize_from_buf<T: DeserializeOwned>(
+    buf: &mut impl ReadBuf<u8>,
+) -> Result<T, SerdeError> {
+    buf.read_transaction(|buf| T::deserialize(&mut BufDeserializer::new(BufSource::new(buf))))
+}
+
+/// Deserializes a value borrowing `&str`/`&[u8]` from the continuous readable region of `buf`.
+///
+/// Returns the value and the number of bytes it took, the caller advances `buf` once the value
+/// is dropped. Data past the end of [`ReadBuf::get_continuous`] is treated as missing.
+pub fn deserialize_borrowed<'de, T: Deserialize<'de>>(
+    buf: &'de impl ReadBuf<u8>,
+) -> Result<(T, usize), SerdeError> {
+    let slice = unsafe { buf.get_continuous(buf.remaining()) };
+    let mut deserializer = BufDeserializer::new(SliceSource::new(slice));
+    let value = T::deserialize(&mut deserializer)?;
+    Ok((value, slice.len() - deserializer.source.slice.len()))
+}
+
+/// Compact binary serializer writing straight into a [`WriteBuf`].
+///
+/// Integers wider than a byte are LEB128 varints, zigzag encoded when signed, except for
+/// `u128`/`i128` and floats which are little endian. Lengths and enum variants are varints.
+pub struct BufSerializer<'a, W: WriteBuf<u8> + ?Sized> {
+    buf: &'a mut W,
+}
+
+impl<'a, W: WriteBuf<u8> + ?Sized> BufSerializer<'a, W> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut W) -> Self {
+        Self { buf }
+    }
+
+    #[inline(always)]
+    fn write_len(&mut self, len: usize) -> Result<(), SerdeError> {
+        Ok(self.buf.try_write_var_u64(len as u64)?)
+    }
+}
+
+macro_rules! serialize_methods {
+    ($($method:ident($ty:ty) => |$self:ident, $v:ident| $body:expr;)*) => {
+        $(
+            #[inline(always)]
+            fn $method($self, $v: $ty) -> Result<(), SerdeError> {
+                Ok($body?)
+            }
+        )*
+    };
+}
+
+impl<W: WriteBuf<u8> + ?Sized> ser::Serializer for &mut BufSerializer<'_, W> {
+    type Ok = ();
+    type Error = SerdeError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_methods! {
+        serialize_bool(bool) => |self, v| self.buf.try_write_u8(v as u8);
+        serialize_u8(u8) => |self, v| self.buf.try_write_u8(v);
+        serialize_i8(i8) => |self, v| self.buf.try_write_i8(v);
+        serialize_u16(u16) => |self, v| self.buf.try_write_var_u64(v as u64);
+        serialize_u32(u32) => |self, v| self.buf.try_write_var_u64(v as u64);
+        serialize_u64(u64) => |self, v| self.buf.try_write_var_u64(v);
+        serialize_i16(i16) => |self, v| self.buf.try_write_zigzag_i64(v as i64);
+        serialize_i32(i32) => |self, v| self.buf.try_write_zigzag_i64(v as i64);
+        serialize_i64(i64) => |self, v| self.buf.try_write_zigzag_i64(v);
+        serialize_u128(u128) => |self, v| self.buf.try_write_u128_le(v);
+        serialize_i128(i128) => |self, v| self.buf.try_write_i128_le(v);
+        serialize_f32(f32) => |self, v| self.buf.try_write_f32_le(v);
+        serialize_f64(f64) => |self, v| self.buf.try_write_f64_le(v);
+        serialize_char(char) => |self, v| self.buf.try_write_var_u32(v as u32);
+    }
+
+    #[inline(always)]
+    fn serialize_str(self, v: &str) -> Result<(), SerdeError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    #[inline(always)]
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), SerdeError> {
+        self.write_len(v.len())?;
+        Ok(self.buf.try_write(v)?)
+    }
+
+    #[inline(always)]
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        Ok(self.buf.try_write_u8(0)?)
+    }
+
+    #[inline(always)]
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        self.buf.try_write_u8(1)?;
+        value.serialize(self)
+    }
+
+    #[inline(always)]
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        self.serialize_u32(variant_index)
+    }
+
+    #[inline(always)]
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    #[inline(always)]
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.serialize_u32(variant_index)?;
+        value.serialize(self)
+    }
+
+    #[inline(always)]
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, SerdeError> {
+        self.write_len(len.ok_or(SerdeError::UnknownLength)?)?;
+        Ok(self)
+    }
+
+    #[inline(always)]
+    fn serialize_tuple(self, _len: usize) -> Result<Self, SerdeError> {
+        Ok(self)
+    }
+
+    #[inline(always)]
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, SerdeError> {
+        Ok(self)
+    }
+
+    #[inline(always)]
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, SerdeError> {
+        self.serialize_u32(variant_index)?;
+        Ok(self)
+    }
+
+    #[inline(always)]
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, SerdeError> {
+        self.write_len(len.ok_or(SerdeError::UnknownLength)?)?;
+        Ok(self)
+    }
+
+    #[inline(always)]
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, SerdeError> {
+        Ok(self)
+    }
+
+    #[inline(always)]
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, SerdeError> {
+        self.serialize_u32(variant_index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! serialize_compound {
+    ($($trait:ident, $method:ident $(, $key:ident)?;)*) => {
+        $(
+            impl<W: WriteBuf<u8> + ?Sized> ser::$trait for &mut BufSerializer<'_, W> {
+                type Ok = ();
+                type Error = SerdeError;
+
+                #[inline(always)]
+                fn $method<T: Serialize + ?Sized>(
+                    &mut self,
+                    $($key: &'static str,)?
+                    value: &T,
+                ) -> Result<(), SerdeError> {
+                    $(let _ = $key;)?
+                    value.serialize(&mut **self)
+                }
+
+                #[inline(always)]
+                fn end(self) -> Result<(), SerdeError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+serialize_compound! {
+    SerializeSeq, serialize_element;
+    SerializeTuple, serialize_element;
+    SerializeTupleStruct, serialize_field;
+    SerializeTupleVariant, serialize_field;
+    SerializeStruct, serialize_field, key;
+    SerializeStructVariant, serialize_field, key;
+}
+
+impl<W: WriteBuf<u8> + ?Sized> ser::SerializeMap for &mut BufSerializer<'_, W> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    #[inline(always)]
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        key.serialize(&mut **self)
+    }
+
+    #[inline(always)]
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(&mut **self)
+    }
+
+    #[inline(always)]
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+/// Bytes handed out by a [`Source`], either borrowed for `'de` or only until the next read.
+pub enum Reference<'de, 's> {
+    Borrowed(&'de [u8]),
+    Transient(&'s [u8]),
+}
+
+/// Input of a [`BufDeserializer`].
+pub trait Source<'de> {
+    fn read_array<const LEN: usize>(&mut self) -> Result<[u8; LEN], SerdeError>;
+    fn read_var_u64(&mut self) -> Result<u64, SerdeError>;
+    fn read_bytes<'s>(&'s mut self, len: usize) -> Result<Reference<'de, 's>, SerdeError>;
+}
+
+/// Reads through [`ReadBuf`], gathering values that cross the wrap point of a ring.
+pub struct BufSource<'a, R: ReadBuf<u8> + ?Sized> {
+    buf: &'a mut R,
+    scratch: Vec<u8>,
+}
+
+impl<'a, R: ReadBuf<u8> + ?Sized> BufSource<'a, R> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut R) -> Self {
+        Self {
+            buf,
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl<'de, R: ReadBuf<u8> + ?Sized> Source<'de> for BufSource<'_, R> {
+    #[inline(always)]
+    fn read_array<const LEN: usize>(&mut self) -> Result<[u8; LEN], SerdeError> {
+        Ok(self.buf.try_read_array()?)
+    }
+
+    #[inline(always)]
+    fn read_var_u64(&mut self) -> Result<u64, SerdeError> {
+        Ok(self.buf.try_read_var_u64()?)
+    }
+
+    #[inline(always)]
+    fn read_bytes<'s>(&'s mut self, len: usize) -> Result<Reference<'de, 's>, SerdeError> {
+        if self.buf.remaining() < len {
+            return Err(ReadBufferError::BufferUnderflow.into());
+        }
+        if unsafe { self.buf.get_continuous(len) }.len() == len {
+            return Ok(Reference::Transient(self.buf.read(len)));
+        }
+        self.scratch.clear();
+        while self.scratch.len() < len {
+            let slice = self.buf.read(len - self.scratch.len());
+            self.scratch.extend_from_slice(slice);
+        }
+        Ok(Reference::Transient(&self.scratch))
+    }
+}
+
+/// Reads from a slice that lives for `'de`, so strings and bytes are borrowed.
+pub struct SliceSource<'de> {
+    slice: &'de [u8],
+}
+
+impl<'de> SliceSource<'de> {
+    #[inline(always)]
+    pub fn new(slice: &'de [u8]) -> Self {
+        Self { slice }
+    }
+}
+
+impl<'de> Source<'de> for SliceSource<'de> {
+    #[inline(always)]
+    fn read_array<const LEN: usize>(&mut self) -> Result<[u8; LEN], SerdeError> {
+        let (array, rest) = self
+            .slice
+            .split_first_chunk()
+            .ok_or(ReadBufferError::BufferUnderflow)?;
+        self.slice = rest;
+        Ok(*array)
+    }
+
+    #[inline(always)]
+    fn read_var_u64(&mut self) -> Result<u64, SerdeError> {
+        let (value, len) = decode_unsigned::<MAX_VAR_U64_LEN, 64>(self.slice)?;
+        self.slice = &self.slice[len..];
+        Ok(value)
+    }
+
+    #[inline(always)]
+    fn read_bytes<'s>(&'s mut self, len: usize) -> Result<Reference<'de, 's>, SerdeError> {
+        let (bytes, rest) = self
+            .slice
+            .split_at_checked(len)
+            .ok_or(ReadBufferError::BufferUnderflow)?;
+        self.slice = rest;
+        Ok(Reference::Borrowed(bytes))
+    }
+}
+
+/// Deserializer for the format written by [`BufSerializer`].
+pub struct BufDeserializer<S> {
+    source: S,
+}
+
+impl<'de, S: Source<'de>> BufDeserializer<S> {
+    #[inline(always)]
+    pub fn new(source: S) -> Self {
+        Self { source }
+    }
+
+    #[inline(always)]
+    pub fn into_source(self) -> S {
+        self.source
+    }
+
+    #[inline(always)]
+    fn read_u8(&mut self) -> Result<u8, SerdeError> {
+        Ok(self.source.read_array::<1>()?[0])
+    }
+
+    #[inline(always)]
+    fn read_unsigned(&mut self, max: u64) -> Result<u64, SerdeError> {
+        let value = self.source.read_var_u64()?;
+        if value > max {
+            return Err(SerdeError::InvalidVarInt);
+        }
+        Ok(value)
+    }
+
+    #[inline(always)]
+    fn read_signed(&mut self, min: i64, max: i64) -> Result<i64, SerdeError> {
+        let value = zigzag_decode_64(self.source.read_var_u64()?);
+        if value < min || value > max {
+            return Err(SerdeError::InvalidVarInt);
+        }
+        Ok(value)
+    }
+
+    #[inline(always)]
+    fn read_len(&mut self) -> Result<usize, SerdeError> {
+        Ok(self.read_unsigned(usize::MAX as u64)? as usize)
+    }
+}
+
+macro_rules! deserialize_methods {
+    ($($method:ident => $visit:ident(|$self:ident| $read:expr);)*) => {
+        $(
+            #[inline(always)]
+            fn $method<V: Visitor<'de>>($self, visitor: V) -> Result<V::Value, SerdeError> {
+                visitor.$visit($read)
+            }
+        )*
+    };
+}
+
+impl<'de, S: Source<'de>> de::Deserializer<'de> for &mut BufDeserializer<S> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, SerdeError> {
+        Err(SerdeError::AnyNotSupported)
+    }
+
+    deserialize_methods! {
+        deserialize_u8 => visit_u8(|self| self.read_u8()?);
+        deserialize_i8 => visit_i8(|self| self.read_u8()? as i8);
+        deserialize_u16 => visit_u16(|self| self.read_unsigned(u16::MAX as u64)? as u16);
+        deserialize_u32 => visit_u32(|self| self.read_unsigned(u32::MAX as u64)? as u32);
+        deserialize_u64 => visit_u64(|self| self.read_unsigned(u64::MAX)?);
+        deserialize_i16 => visit_i16(|self| self.read_signed(i16::MIN as i64, i16::MAX as i64)? as i16);
+        deserialize_i32 => visit_i32(|self| self.read_signed(i32::MIN as i64, i32::MAX as i64)? as i32);
+        deserialize_i64 => visit_i64(|self| self.read_signed(i64::MIN, i64::MAX)?);
+        deserialize_u128 => visit_u128(|self| u128::from_le_bytes(self.source.read_array()?));
+        deserialize_i128 => visit_i128(|self| i128::from_le_bytes(self.source.read_array()?));
+        deserialize_f32 => visit_f32(|self| f32::from_le_bytes(self.source.read_array()?));
+        deserialize_f64 => visit_f64(|self| f64::from_le_bytes(self.source.read_array()?));
+    }
+
+    #[inline(always)]
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.read_u8()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            byte => Err(SerdeError::InvalidBool(byte)),
+        }
+    }
+
+    #[inline(always)]
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let value = self.read_unsigned(u32::MAX as u64)? as u32;
+        visitor.visit_char(char::from_u32(value).ok_or(SerdeError::InvalidChar(value))?)
+    }
+
+    #[inline(always)]
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let len = self.read_len()?;
+        match self.source.read_bytes(len)? {
+            Reference::Borrowed(bytes) => visitor.visit_borrowed_str(
+                core::str::from_utf8(bytes).map_err(|_| SerdeError::InvalidUtf8)?,
+            ),
+            Reference::Transient(bytes) => {
+                visitor.visit_str(core::str::from_utf8(bytes).map_err(|_| SerdeError::InvalidUtf8)?)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_str(visitor)
+    }
+
+    #[inline(always)]
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let len = self.read_len()?;
+        match self.source.read_bytes(len)? {
+            Reference::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Reference::Transient(bytes) => visitor.visit_bytes(bytes),
+        }
+    }
+
+    #[inline(always)]
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    #[inline(always)]
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.read_u8()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            tag => Err(SerdeError::InvalidOptionTag(tag)),
+        }
+    }
+
+    #[inline(always)]
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    #[inline(always)]
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+
+    #[inline(always)]
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline(always)]
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let len = self.read_len()?;
+        visitor.visit_seq(Counted { de: self, len })
+    }
+
+    #[inline(always)]
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_seq(Counted { de: self, len })
+    }
+
+    #[inline(always)]
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_seq(Counted { de: self, len })
+    }
+
+    #[inline(always)]
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let len = self.read_len()?;
+        visitor.visit_map(Counted { de: self, len })
+    }
+
+    #[inline(always)]
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_seq(Counted {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    #[inline(always)]
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, SerdeError> {
+        Err(SerdeError::AnyNotSupported)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, SerdeError> {
+        Err(SerdeError::AnyNotSupported)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct Counted<'a, S> {
+    de: &'a mut BufDeserializer<S>,
+    len: usize,
+}
+
+impl<'de, S: Source<'de>> de::SeqAccess<'de> for Counted<'_, S> {
+    type Error = SerdeError;
+
+    #[inline(always)]
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, S: Source<'de>> de::MapAccess<'de> for Counted<'_, S> {
+    type Error = SerdeError;
+
+    #[inline(always)]
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    #[inline(always)]
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, S: Source<'de>> de::EnumAccess<'de> for &mut BufDeserializer<S> {
+    type Error = SerdeError;
+    type Variant = Self;
+
+    #[inline(always)]
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), SerdeError> {
+        let index = self.read_unsigned(u32::MAX as u64)? as u32;
+        let value = seed.deserialize(IntoDeserializer::<SerdeError>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, S: Source<'de>> de::VariantAccess<'de> for &mut BufDeserializer<S> {
+    type Error = SerdeError;
+
+    #[inline(always)]
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, SerdeError> {
+        seed.deserialize(self)
+    }
+
+    #[inline(always)]
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    #[inline(always)]
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{Buffer, RingBuffer};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind {
+        Ping,
+        Move { x: i32, y: i32 },
+        Chat(String),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Packet {
+        id: u16,
+        flags: (bool, u8),
+        kinds: Vec<Kind>,
+        tags: BTreeMap<String, Option<u64>>,
+        ratio: f64,
+    }
+
+    fn packet() -> Packet {
+        Packet {
+            id: 300,
+            flags: (true, 7),
+            kinds: vec![
+                Kind::Ping,
+                Kind::Move { x: -1, y: 70000 },
+                Kind::Chat("hi".into()),
+            ],
+            tags: BTreeMap::from([("a".into(), Some(u64::MAX)), ("b".into(), None)]),
+            ratio: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_across_wrap() {
+        let mut buffer: RingBuffer<[u8; 64]> = RingBuffer::new();
+        buffer.write(&[0; 40]);
+        buffer.advance(40);
+        serialize_to_buf(&packet(), &mut buffer).unwrap();
+        assert_eq!(
+            deserialize_from_buf::<Packet>(&mut buffer).unwrap(),
+            packet()
+        );
+        assert_eq!(buffer.remaining(), 0);
+    }
+
+    #[test]
+    fn test_exhaustion() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        assert!(matches!(
+            serialize_to_buf(&packet(), &mut buffer),
+            Err(SerdeError::Write(WriteBufferError::BufferFull))
+        ));
+        assert_eq!(buffer.remaining(), 0);
+
+        serialize_to_buf(&(1u8, "hello"), &mut buffer).unwrap();
+        let len = buffer.remaining();
+        unsafe { buffer.set_filled_pos(len - 1) };
+        assert!(matches!(
+            deserialize_from_buf::<(u8, String)>(&mut buffer),
+            Err(SerdeError::Read(ReadBufferError::BufferUnderflow))
+        ));
+        assert_eq!(buffer.remaining(), len - 1);
+    }
+
+    #[test]
+    fn test_borrowed() {
+        #[derive(Serialize, Deserialize)]
+        struct Borrowed<'a> {
+            name: &'a str,
+            #[serde(with = "serde_bytes_compat")]
+            data: &'a [u8],
+        }
+
+        mod serde_bytes_compat {
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(data)
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<&'de [u8], D::Error> {
+                <&[u8]>::deserialize(deserializer)
+            }
+        }
+
+        let mut buffer: Buffer<[u8; 32]> = Buffer::new();
+        let value = Borrowed {
+            name: "fastbuf",
+            data: b"\x01\x02",
+        };
+        serialize_to_buf(&value, &mut buffer).unwrap();
+        let (borrowed, len) = deserialize_borrowed::<Borrowed>(&buffer).unwrap();
+        assert_eq!(
+            (borrowed.name, borrowed.data),
+            ("fastbuf", &b"\x01\x02"[..])
+        );
+        assert!(core::ptr::eq(
+            borrowed.name.as_ptr(),
+            unsafe { buffer.get_continuous(2) }[1..].as_ptr()
+        ));
+        buffer.advance(len);
+        assert_eq!(buffer.remaining(), 0);
+    }
+}
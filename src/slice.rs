@@ -0,0 +1,144 @@
+use core::fmt::Debug;
+
+use crate::ReadBuf;
+
+/// Zero-copy [`ReadBuf`] over borrowed data, e.g. a received datagram or a memory-mapped file.
+///
+/// The inherent [`SliceReader::read`] returns slices that outlive the reader.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SliceReader<'a, T> {
+    slice: &'a [T],
+    pos: usize,
+}
+
+impl<'a, T> SliceReader<'a, T> {
+    #[inline(always)]
+    pub const fn new(slice: &'a [T]) -> Self {
+        Self { slice, pos: 0 }
+    }
+
+    /// Reads up to `len` elements, borrowing from the underlying slice rather than the reader.
+    #[inline(always)]
+    pub fn read(&mut self, len: usize) -> &'a [T] {
+        let slice_len = core::cmp::min(len, self.slice.len() - self.pos);
+        let data = &self.slice[self.pos..self.pos + slice_len];
+        self.pos += slice_len;
+        data
+    }
+
+    /// The unread elements.
+    #[inline(always)]
+    pub fn remaining_slice(&self) -> &'a [T] {
+        &self.slice[self.pos..]
+    }
+
+    #[inline(always)]
+    pub const fn get_ref(&self) -> &'a [T] {
+        self.slice
+    }
+}
+
+impl<'a, T> From<&'a [T]> for SliceReader<'a, T> {
+    #[inline(always)]
+    fn from(slice: &'a [T]) -> Self {
+        Self::new(slice)
+    }
+}
+
+impl<T> ReadBuf<T> for SliceReader<'_, T> {
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[T] {
+        SliceReader::read(self, len)
+    }
+
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[T] {
+        let slice_len = core::cmp::min(len, self.slice.len() - self.pos);
+        &self.slice[self.pos..self.pos + slice_len]
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.slice.len() - self.pos
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        self.pos = core::cmp::min(self.slice.len(), self.pos + len);
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        debug_assert!(pos <= self.slice.len());
+        self.pos = pos;
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Read for SliceReader<'_, u8> {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let data = SliceReader::read(self, buf.len());
+        buf[..data.len()].copy_from_slice(data);
+        Ok(data.len())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::BufRead for SliceReader<'_, u8> {
+    #[inline(always)]
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.remaining_slice())
+    }
+
+    #[inline(always)]
+    fn consume(&mut self, amt: usize) {
+        self.advance(amt)
+    }
+}
+
+impl<T: Debug> Debug for SliceReader<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.remaining_slice().fmt(f)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::{Endian, Frame, FrameCodec, LengthPrefix, ReadBufExt, ReadDelimited};
+
+    #[test]
+    fn test_read_outlives_reader() {
+        let data = b"GET / HTTP/1.1\r\n".to_vec();
+        let (method, rest) = {
+            let mut reader = SliceReader::new(&data[..]);
+            let method = reader.read(3);
+            reader.advance(1);
+            assert_eq!(reader.read_line(16), Ok(&b"/ HTTP/1.1"[..]));
+            (method, reader.remaining_slice())
+        };
+        assert_eq!(method, b"GET");
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_decode_without_copying() {
+        let data = [0, 0, 0, 3, b'a', b'b', b'c', 0xff];
+        let mut reader = SliceReader::from(&data[..]);
+        let codec = FrameCodec::new(LengthPrefix::U32(Endian::Big));
+        match codec.decode(&mut reader) {
+            Ok(Frame::Borrowed(payload)) => assert_eq!(payload.as_ptr(), data[4..].as_ptr()),
+            frame => panic!("unexpected {frame:?}"),
+        }
+        assert_eq!(reader.try_read_u8(), Ok(0xff));
+        assert!(reader.try_read_u8().is_err());
+        assert_eq!(reader.pos(), 8);
+    }
+}
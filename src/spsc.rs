@@ -0,0 +1,477 @@
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
+};
+use std::alloc::Allocator;
+
+#[cfg(loom)]
+use loom::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+#[cfg(not(loom))]
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{
+    buffer::ALLOC,
+    const_min,
+    ring::{physical, wrap_pos},
+    unsafe_wild_copy, BufIndex, Chunk, ChunkBuilder, ReadBuf, ReadBufMut, RingBuffer, WriteBuf,
+    WriteBufferError,
+};
+
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+struct Shared<C> {
+    filled_pos: CachePadded<AtomicUsize>,
+    pos: CachePadded<AtomicUsize>,
+    chunk: UnsafeCell<C>,
+}
+
+/// Writing half of a [`RingBuffer`] split with [`RingBuffer::split`].
+///
+/// Data becomes visible to the [`RingConsumer`] as soon as a write returns.
+pub struct RingProducer<S, A: Allocator = ALLOC, C = S> {
+    shared: Arc<Shared<C>>,
+    ptr: *mut S,
+    filled_pos: usize,
+    _marker: PhantomData<A>,
+}
+
+/// Reading half of a [`RingBuffer`] split with [`RingBuffer::split`].
+///
+/// Reading doesn't hand space back to the producer, call [`RingConsumer::release`] once the
+/// data before `pos` is no longer needed. Until then `pos` can be rolled back freely, e.g. by a
+/// checkpoint after an incomplete frame.
+pub struct RingConsumer<S, A: Allocator = ALLOC, C = S> {
+    shared: Arc<Shared<C>>,
+    ptr: *mut S,
+    pos: usize,
+    released_pos: usize,
+    _marker: PhantomData<A>,
+}
+
+unsafe impl<S, A: Allocator, C: Send> Send for RingProducer<S, A, C> {}
+unsafe impl<S, A: Allocator, C: Send> Send for RingConsumer<S, A, C> {}
+
+#[inline(always)]
+const fn len_between<const N: usize>(pos: usize, filled_pos: usize) -> usize {
+    if filled_pos >= pos {
+        filled_pos - pos
+    } else {
+        filled_pos + 2 * N - pos
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>, L: BufIndex>
+    RingBuffer<[T; N], A, C, L>
+{
+    /// Splits the ring into a producer and a consumer that can be sent to different threads.
+    ///
+    /// Data already in the ring stays readable through the consumer.
+    #[allow(clippy::type_complexity)]
+    pub fn split(self) -> (RingProducer<[T; N], A, C>, RingConsumer<[T; N], A, C>) {
+        let filled_pos = self.filled_pos.to_usize();
+        let pos = self.pos.to_usize();
+        let shared = Arc::new(Shared {
+            filled_pos: CachePadded(AtomicUsize::new(filled_pos)),
+            pos: CachePadded(AtomicUsize::new(pos)),
+            chunk: UnsafeCell::new(self.chunk),
+        });
+        let ptr = unsafe { (*shared.chunk.get()).as_mut_ptr() } as *mut [T; N];
+        (
+            RingProducer {
+                shared: shared.clone(),
+                ptr,
+                filled_pos,
+                _marker: PhantomData,
+            },
+            RingConsumer {
+                shared,
+                ptr,
+                pos,
+                released_pos: pos,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C> RingProducer<[T; N], A, C> {
+    /// Copies `data` after `filled_pos` and publishes it, callers check the remaining space beforehand.
+    #[inline(always)]
+    fn copy_in(&mut self, data: &[T]) {
+        let index = physical::<N>(self.filled_pos);
+        let first_len = const_min!(data.len(), N - index);
+        let second_len = data.len() - first_len;
+        let src = data.as_ptr();
+        let dst = self.ptr as *mut T;
+        unsafe {
+            src.copy_to_nonoverlapping(dst.add(index), first_len);
+            src.add(first_len).copy_to_nonoverlapping(dst, second_len);
+        }
+        self.publish(data.len());
+    }
+
+    #[inline(always)]
+    fn publish(&mut self, len: usize) {
+        self.filled_pos = wrap_pos::<N>(self.filled_pos + len);
+        self.shared
+            .filled_pos
+            .0
+            .store(self.filled_pos, Ordering::Release);
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> Chunk<T>
+    for RingProducer<[T; N], A, C>
+{
+    /// Always empty, published data belongs to the consumer.
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        &[]
+    }
+
+    /// Always empty, published data belongs to the consumer.
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut []
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.ptr as *const T
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr as *mut T
+    }
+}
+
+impl<T: Copy, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> WriteBuf<T>
+    for RingProducer<[T; N], A, C>
+{
+    #[inline(always)]
+    fn write(&mut self, data: &[T]) {
+        assert!(data.len() <= self.remaining_space(), "ring buffer overflow");
+        self.copy_in(data);
+    }
+
+    #[inline(always)]
+    fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
+        if data.len() <= self.remaining_space() {
+            self.copy_in(data);
+            Ok(())
+        } else {
+            Err(WriteBufferError::BufferFull)
+        }
+    }
+
+    #[inline(always)]
+    fn try_write_fast<const LEN: usize>(
+        &mut self,
+        data: &[T; LEN],
+    ) -> Result<(), WriteBufferError> {
+        if LEN > self.remaining_space() {
+            return Err(WriteBufferError::BufferFull);
+        }
+        let index = physical::<N>(self.filled_pos);
+        if index + LEN <= N {
+            let src_ptr = data.as_ptr();
+            let dst_ptr = (self.ptr as *mut T).wrapping_add(index);
+            unsafe {
+                unsafe_wild_copy!([T; LEN], src_ptr, dst_ptr, LEN);
+            }
+            self.publish(LEN);
+        } else {
+            self.copy_in(data);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn remaining_space(&self) -> usize {
+        let pos = self.shared.pos.0.load(Ordering::Acquire);
+        N - len_between::<N>(pos, self.filled_pos)
+    }
+
+    #[inline(always)]
+    fn filled_pos(&self) -> usize {
+        self.filled_pos
+    }
+
+    #[inline(always)]
+    unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+        self.filled_pos = filled_pos;
+        self.shared
+            .filled_pos
+            .0
+            .store(filled_pos, Ordering::Release);
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let index = physical::<N>(self.filled_pos);
+        let len = const_min!(self.remaining_space(), N - index);
+        unsafe { &mut *slice_from_raw_parts_mut((self.ptr as *mut MaybeUninit<T>).add(index), len) }
+    }
+
+    #[inline(always)]
+    unsafe fn commit(&mut self, len: usize) {
+        debug_assert!(len <= self.remaining_space());
+        self.publish(len);
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C> RingConsumer<[T; N], A, C> {
+    #[inline(always)]
+    fn readable_lens(&self) -> (usize, usize) {
+        let filled_pos = self.shared.filled_pos.0.load(Ordering::Acquire);
+        let len = len_between::<N>(self.pos, filled_pos);
+        let first_len = const_min!(len, N - physical::<N>(self.pos));
+        (first_len, len - first_len)
+    }
+
+    /// Hands the space before `pos` back to the producer. `pos` can't be rolled back past it
+    /// afterwards.
+    #[inline(always)]
+    pub fn release(&mut self) {
+        if self.released_pos != self.pos {
+            self.released_pos = self.pos;
+            self.shared.pos.0.store(self.pos, Ordering::Release);
+        }
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> Chunk<T>
+    for RingConsumer<[T; N], A, C>
+{
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        unsafe { self.get_continuous(N) }
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { self.get_continuous_mut(N) }
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.ptr as *const T
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr as *mut T
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> ReadBuf<T>
+    for RingConsumer<[T; N], A, C>
+{
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[T] {
+        let pos = self.pos;
+        let slice_len = const_min!(len, self.readable_lens().0);
+        self.pos = wrap_pos::<N>(pos + slice_len);
+        unsafe {
+            &*slice_from_raw_parts(
+                (self.ptr as *const T).wrapping_add(physical::<N>(pos)),
+                slice_len,
+            )
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[T] {
+        let slice_len = const_min!(len, self.readable_lens().0);
+        let index = physical::<N>(self.pos);
+        unsafe { &*slice_from_raw_parts((self.ptr as *const T).wrapping_add(index), slice_len) }
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        let (first_len, second_len) = self.readable_lens();
+        first_len + second_len
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        let len = const_min!(len, self.remaining());
+        self.pos = wrap_pos::<N>(self.pos + len);
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        let filled_pos = self.shared.filled_pos.0.load(Ordering::Acquire);
+        assert!(
+            len_between::<N>(self.released_pos, pos)
+                <= len_between::<N>(self.released_pos, filled_pos),
+            "position was already handed back to the producer"
+        );
+        self.pos = pos;
+    }
+}
+
+impl<T, A: Allocator, const N: usize, C: ChunkBuilder<A> + Chunk<T>> ReadBufMut<T>
+    for RingConsumer<[T; N], A, C>
+{
+    #[inline(always)]
+    unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+        let slice_len = const_min!(len, self.readable_lens().0);
+        let index = physical::<N>(self.pos);
+        unsafe {
+            &mut *slice_from_raw_parts_mut((self.ptr as *mut T).wrapping_add(index), slice_len)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(loom))]
+    #[test]
+    fn test_split_keeps_data() {
+        let mut buffer: RingBuffer<[u8; 8]> = RingBuffer::new();
+        buffer.write(b"abcdef");
+        buffer.advance(4);
+        let (mut producer, mut consumer) = buffer.split();
+
+        assert_eq!(producer.remaining_space(), 6);
+        producer.write(b"ghij");
+        assert_eq!(consumer.remaining(), 6);
+        assert_eq!(consumer.read(8), b"efgh");
+        assert_eq!(producer.remaining_space(), 2);
+        consumer.release();
+        assert_eq!(producer.remaining_space(), 6);
+        assert_eq!(consumer.read(8), b"ij");
+        consumer.release();
+        assert_eq!(producer.remaining_space(), 8);
+    }
+
+    #[cfg(not(loom))]
+    #[test]
+    fn test_try_write_fast_wraps_around() {
+        let (mut producer, mut consumer) = RingBuffer::<[u8; 8]>::new().split();
+        producer.try_write_fast(b"abcdef").unwrap();
+        consumer.advance(6);
+        consumer.release();
+        producer.try_write_fast(b"ghij").unwrap();
+        assert!(producer.try_write_fast(b"klmnop").is_err());
+        assert_eq!(consumer.read(8), b"gh");
+        assert_eq!(consumer.read(8), b"ij");
+        consumer.release();
+        producer.try_write_fast(b"klm").unwrap();
+        assert_eq!(consumer.read(8), b"klm");
+    }
+
+    #[cfg(not(loom))]
+    #[test]
+    fn test_rollback() {
+        use crate::{ReadBufCheckpoint, ReadBufExt, ReadBufferError, ReadVarInt};
+
+        let (mut producer, mut consumer) = RingBuffer::<[u8; 8]>::new().split();
+        producer.write(&[0; 6]);
+        consumer.advance(6);
+        consumer.release();
+        producer.write(&[1, 2]);
+        assert_eq!(
+            consumer.read_transaction(|c| {
+                c.try_read_u8()?;
+                c.try_read_u32_be()
+            }),
+            Err(ReadBufferError::BufferUnderflow)
+        );
+        assert_eq!(consumer.remaining(), 2);
+        assert_eq!(consumer.try_read_u16_be(), Ok(0x0102));
+        consumer.release();
+
+        // Incomplete varint wrapping around the end of the ring.
+        producer.write(&[0; 6]);
+        consumer.advance(6);
+        consumer.release();
+        producer.write(&[0x80, 0x80, 0x80]);
+        assert!(consumer.try_read_var_u32().is_err());
+        assert_eq!(consumer.remaining(), 3);
+        producer.write(&[0x01]);
+        assert_eq!(consumer.try_read_var_u32(), Ok(1 << 21));
+        consumer.release();
+        assert_eq!(producer.remaining_space(), 8);
+    }
+
+    #[cfg(not(loom))]
+    #[test]
+    fn test_threads() {
+        use crate::{ReadBufExt, WriteBufExt};
+
+        const COUNT: u32 = 10_000;
+        let (mut producer, mut consumer) = RingBuffer::<[u8; 64]>::new().split();
+        let handle = std::thread::spawn(move || {
+            for value in 0..COUNT {
+                while producer.try_write_u32_le(value).is_err() {
+                    std::thread::yield_now();
+                }
+            }
+        });
+        for value in 0..COUNT {
+            loop {
+                if let Ok(read) = consumer.try_read_u32_le() {
+                    assert_eq!(read, value);
+                    consumer.release();
+                    break;
+                }
+                std::thread::yield_now();
+            }
+        }
+        handle.join().unwrap();
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn loom_wrap_around() {
+        loom::model(|| {
+            let (mut producer, mut consumer) = RingBuffer::<[u8; 4]>::new().split();
+            let handle = loom::thread::spawn(move || {
+                let mut next = 0;
+                while next < 6 {
+                    match producer.try_write(&[next, next + 1]) {
+                        Ok(()) => next += 2,
+                        Err(_) => loom::thread::yield_now(),
+                    }
+                }
+            });
+            let mut expected = 0;
+            while expected < 6 {
+                let data = consumer.read(3);
+                if data.is_empty() {
+                    loom::thread::yield_now();
+                }
+                for &value in data {
+                    assert_eq!(value, expected);
+                    expected += 1;
+                }
+                consumer.release();
+            }
+            handle.join().unwrap();
+        });
+    }
+}
@@ -0,0 +1,303 @@
+use core::{marker::PhantomData, mem::MaybeUninit};
+
+use crate::{Chunk, ReadBuf, ReadBufMut, WriteBuf, WriteBufferError};
+
+/// [`ReadBuf`] that only sees the next `limit` elements of `buf`, e.g. a length delimited
+/// sub-message handed to a nested decoder.
+///
+/// Reads advance `buf` directly, so whatever the nested decoder consumed stays consumed.
+pub struct Take<'a, T, B: ReadBuf<T> + ?Sized> {
+    buf: &'a mut B,
+    limit: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, B: ReadBuf<T> + ?Sized> Take<'a, T, B> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut B, limit: usize) -> Self {
+        Self {
+            buf,
+            limit,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Elements left before the limit, which can be more than [`ReadBuf::remaining`].
+    #[inline(always)]
+    pub fn remaining_limit(&self) -> usize {
+        self.limit
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> &'a mut B {
+        self.buf
+    }
+}
+
+impl<T, B: ReadBuf<T> + ?Sized> ReadBuf<T> for Take<'_, T, B> {
+    #[inline(always)]
+    fn read(&mut self, len: usize) -> &[T] {
+        let data = self.buf.read(core::cmp::min(len, self.limit));
+        self.limit -= data.len();
+        data
+    }
+
+    #[inline(always)]
+    unsafe fn get_continuous(&self, len: usize) -> &[T] {
+        self.buf.get_continuous(core::cmp::min(len, self.limit))
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        core::cmp::min(self.buf.remaining(), self.limit)
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        let len = core::cmp::min(len, self.remaining());
+        self.buf.advance(len);
+        self.limit -= len;
+    }
+
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.buf.pos()
+    }
+
+    /// Moving `pos` back, e.g. rolling back a checkpoint, gives the elements back to the limit.
+    #[inline(always)]
+    unsafe fn set_pos(&mut self, pos: usize) {
+        let remaining = self.buf.remaining();
+        self.buf.set_pos(pos);
+        self.limit = (self.limit + self.buf.remaining()).saturating_sub(remaining);
+    }
+}
+
+impl<T, B: ReadBufMut<T> + ?Sized> ReadBufMut<T> for Take<'_, T, B> {
+    #[inline(always)]
+    unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+        self.buf.get_continuous_mut(core::cmp::min(len, self.limit))
+    }
+}
+
+/// [`WriteBuf`] that accepts at most `limit` more elements before reporting
+/// [`WriteBufferError::BufferFull`].
+pub struct Limit<'a, T, B: WriteBuf<T> + ?Sized> {
+    buf: &'a mut B,
+    limit: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T, B: WriteBuf<T> + ?Sized> Limit<'a, T, B> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut B, limit: usize) -> Self {
+        Self {
+            buf,
+            limit,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Elements left before the limit, which can be more than [`WriteBuf::remaining_space`].
+    #[inline(always)]
+    pub fn remaining_limit(&self) -> usize {
+        self.limit
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> &'a mut B {
+        self.buf
+    }
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> Chunk<T> for Limit<'_, T, B> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[T] {
+        self.buf.as_slice()
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self.buf.as_mut_slice()
+    }
+
+    #[inline(always)]
+    fn as_ptr(&self) -> *const T {
+        self.buf.as_ptr()
+    }
+
+    #[inline(always)]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr()
+    }
+
+    #[inline(always)]
+    unsafe fn set_init(&mut self, len: usize) {
+        self.buf.set_init(len)
+    }
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> WriteBuf<T> for Limit<'_, T, B> {
+    #[inline(always)]
+    fn write(&mut self, data: &[T]) {
+        assert!(data.len() <= self.limit, "write exceeds the limit");
+        self.buf.write(data);
+        self.limit -= data.len();
+    }
+
+    #[inline(always)]
+    fn try_write(&mut self, data: &[T]) -> Result<(), WriteBufferError> {
+        if data.len() > self.limit {
+            return Err(WriteBufferError::BufferFull);
+        }
+        self.buf.try_write(data)?;
+        self.limit -= data.len();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_write_fast<const LEN: usize>(
+        &mut self,
+        data: &[T; LEN],
+    ) -> Result<(), WriteBufferError> {
+        if LEN > self.limit {
+            return Err(WriteBufferError::BufferFull);
+        }
+        self.buf.try_write_fast(data)?;
+        self.limit -= LEN;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn remaining_space(&self) -> usize {
+        core::cmp::min(self.buf.remaining_space(), self.limit)
+    }
+
+    #[inline(always)]
+    fn filled_pos(&self) -> usize {
+        self.buf.filled_pos()
+    }
+
+    /// Moving `filled_pos` back, e.g. rolling back a checkpoint, gives the elements back to the limit.
+    #[inline(always)]
+    unsafe fn set_filled_pos(&mut self, filled_pos: usize) {
+        let remaining_space = self.buf.remaining_space();
+        self.buf.set_filled_pos(filled_pos);
+        self.limit = (self.limit + self.buf.remaining_space()).saturating_sub(remaining_space);
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    #[inline(always)]
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let limit = self.limit;
+        let spare = self.buf.spare_capacity_mut();
+        let len = core::cmp::min(spare.len(), limit);
+        &mut spare[..len]
+    }
+
+    #[inline(always)]
+    unsafe fn commit(&mut self, len: usize) {
+        debug_assert!(len <= self.limit);
+        self.buf.commit(len);
+        self.limit -= len;
+    }
+}
+
+pub trait ReadBufTake<T>: ReadBuf<T> {
+    /// Limits reads to the next `len` elements. Named apart from `io::Read::take`, which most
+    /// byte buffers here implement as well.
+    #[inline(always)]
+    fn take_buf(&mut self, len: usize) -> Take<'_, T, Self> {
+        Take::new(self, len)
+    }
+}
+
+impl<T, B: ReadBuf<T> + ?Sized> ReadBufTake<T> for B {}
+
+pub trait WriteBufLimit<T>: WriteBuf<T> {
+    /// Limits writes to the next `len` elements.
+    #[inline(always)]
+    fn limit_buf(&mut self, len: usize) -> Limit<'_, T, Self> {
+        Limit::new(self, len)
+    }
+}
+
+impl<T, B: WriteBuf<T> + ?Sized> WriteBufLimit<T> for B {}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::{Buffer, ReadBufCheckpoint, ReadBufExt, ReadBufferError, RingBuffer, WriteBufExt};
+
+    use super::*;
+
+    #[test]
+    fn test_nested_tlv() {
+        let mut buffer: Buffer<[u8; 32]> = Buffer::new();
+        buffer.write(&[1, 4, 0, 7, 0, 9, 2, 1, 5]);
+
+        assert_eq!(buffer.try_read_u8(), Ok(1));
+        let len = buffer.try_read_u8().unwrap() as usize;
+        let mut sub = buffer.take_buf(len);
+        assert_eq!(sub.remaining(), 4);
+        assert_eq!(sub.try_read_u16_be(), Ok(7));
+        assert_eq!(
+            sub.read_transaction(|sub| sub.try_read_u32_be()),
+            Err(ReadBufferError::BufferUnderflow)
+        );
+        assert_eq!(sub.remaining_limit(), 2);
+        assert_eq!(sub.try_read_u16_be(), Ok(9));
+        assert_eq!(sub.try_read_u8(), Err(ReadBufferError::BufferUnderflow));
+
+        assert_eq!(buffer.pos(), 6);
+        assert_eq!(buffer.read(3), &[2, 1, 5]);
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut buffer: RingBuffer<[u8; 8]> = RingBuffer::new();
+        buffer.write(&[0; 6]);
+        buffer.advance(6);
+
+        let mut limited = buffer.limit_buf(5);
+        assert_eq!(limited.remaining_space(), 5);
+        assert_eq!(
+            limited.try_write_u32_be(1).and(limited.try_write_u16_be(2)),
+            Err(WriteBufferError::BufferFull)
+        );
+        let spare = limited.spare_capacity_mut();
+        assert_eq!(spare.len(), 1);
+        spare[0].write(b'x');
+        unsafe { limited.commit(1) };
+        assert_eq!(limited.remaining_space(), 0);
+        assert!(limited.try_write_fast(b"x").is_err());
+
+        assert_eq!(buffer.remaining(), 5);
+        assert_eq!(buffer.remaining_space(), 3);
+    }
+
+    #[test]
+    fn test_with_io_read_in_scope() {
+        use std::io::Read;
+
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        let mut limited = buffer.limit_buf(4);
+        assert_eq!(
+            limited.try_write(b"abcdef"),
+            Err(WriteBufferError::BufferFull)
+        );
+        limited.try_write(b"abcd").unwrap();
+        assert_eq!(buffer.remaining(), 4);
+
+        let mut out = Vec::new();
+        (&mut buffer).take(1).read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"a");
+        let mut sub = buffer.take_buf(2);
+        assert_eq!(sub.read(8), b"bc");
+        assert_eq!(buffer.try_read_u8(), Ok(b'd'));
+    }
+}
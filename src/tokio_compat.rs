@@ -0,0 +1,179 @@
+use core::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use std::{alloc::Allocator, io};
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::{const_min, Buf, BufIndex, Buffer, Chunk, ChunkBuilder, ReadBuf, WriteBuf};
+
+/// Async counterpart of [`ReadToBuf`](crate::ReadToBuf) for tokio's [`AsyncRead`].
+pub trait AsyncReadToBuf: AsyncRead {
+    /// Reads once into the spare capacity after `filled_pos` and returns the number of bytes read.
+    ///
+    /// Like tokio's `read_buf`, `Ok(0)` means either end of stream or a buffer without spare capacity.
+    fn poll_read_to_buf(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut impl Buf<u8>,
+    ) -> Poll<io::Result<usize>> {
+        let mut read_buf = tokio::io::ReadBuf::uninit(buf.spare_capacity_mut());
+        ready!(self.poll_read(cx, &mut read_buf))?;
+        let read_length = read_buf.filled().len();
+        unsafe { buf.commit(read_length) };
+        Poll::Ready(Ok(read_length))
+    }
+
+    fn read_to_buf<'a, B: Buf<u8>>(
+        &'a mut self,
+        buf: &'a mut B,
+    ) -> impl Future<Output = io::Result<usize>> + 'a
+    where
+        Self: Unpin,
+    {
+        poll_fn(move |cx| Pin::new(&mut *self).poll_read_to_buf(cx, buf))
+    }
+}
+
+impl<S: AsyncRead + ?Sized> AsyncReadToBuf for S {}
+
+/// Drains the unread region of a [`ReadBuf`] into tokio's [`AsyncWrite`].
+pub trait AsyncWriteFromBuf: AsyncWrite {
+    /// Writes until `buf` has nothing left to read, advancing `pos` after every partial write.
+    ///
+    /// Returns the number of bytes written, a sink that accepts no more bytes fails with
+    /// [`io::ErrorKind::WriteZero`].
+    fn poll_write_from_buf(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut impl ReadBuf<u8>,
+    ) -> Poll<io::Result<usize>> {
+        let mut written = 0;
+        while buf.remaining() != 0 {
+            let data = unsafe { buf.get_continuous(buf.remaining()) };
+            match self.as_mut().poll_write(cx, data) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero)))
+                }
+                Poll::Ready(Ok(write_length)) => {
+                    buf.advance(write_length);
+                    written += write_length;
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending if written != 0 => return Poll::Ready(Ok(written)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    fn write_from_buf<'a, B: ReadBuf<u8>>(
+        &'a mut self,
+        buf: &'a mut B,
+    ) -> impl Future<Output = io::Result<usize>> + 'a
+    where
+        Self: Unpin,
+    {
+        let mut written = 0;
+        poll_fn(move |cx| {
+            while buf.remaining() != 0 {
+                written += ready!(Pin::new(&mut *self).poll_write_from_buf(cx, buf))?;
+            }
+            Poll::Ready(Ok(written))
+        })
+    }
+}
+
+impl<S: AsyncWrite + ?Sized> AsyncWriteFromBuf for S {}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> AsyncRead
+    for Buffer<[u8; N], A, C, L>
+where
+    Self: Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let data = self.get_mut().read(buf.remaining());
+        buf.put_slice(data);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> AsyncWrite
+    for Buffer<[u8; N], A, C, L>
+where
+    Self: Unpin,
+{
+    /// Writes as much of `buf` as fits, `Ok(0)` means the buffer is full.
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let write_length = const_min!(buf.len(), this.remaining_space());
+        this.write(&buf[..write_length]);
+        Poll::Ready(Ok(write_length))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::task::Waker;
+
+    use super::*;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_to_buf() {
+        let mut source: Buffer<[u8; 16]> = Buffer::new();
+        source.write(b"hello world");
+        let mut buffer: Buffer<[u8; 8]> = Buffer::new();
+
+        assert_eq!(block_on(source.read_to_buf(&mut buffer)).unwrap(), 8);
+        assert_eq!(buffer.read(8), b"hello wo");
+        assert_eq!(block_on(source.read_to_buf(&mut buffer)).unwrap(), 0);
+        buffer.clear();
+        assert_eq!(block_on(source.read_to_buf(&mut buffer)).unwrap(), 3);
+        assert_eq!(buffer.read(3), b"rld");
+        assert_eq!(block_on(source.read_to_buf(&mut buffer)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_write_from_buf() {
+        let mut sink: Buffer<[u8; 4]> = Buffer::new();
+        let mut buffer: Buffer<[u8; 8]> = Buffer::new();
+        buffer.write(b"abcdef");
+
+        let err = block_on(sink.write_from_buf(&mut buffer)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(buffer.remaining(), 2);
+        assert_eq!(sink.read(4), b"abcd");
+
+        sink.clear();
+        assert_eq!(block_on(sink.write_from_buf(&mut buffer)).unwrap(), 2);
+        assert_eq!(sink.read(2), b"ef");
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{declare_const_impl, declare_const_trait};
-use core::ops::{Deref, DerefMut};
+use core::{
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+};
 use std::alloc::Allocator;
 
 declare_const_trait! {
@@ -12,16 +15,25 @@ declare_const_trait! {
 
 declare_const_trait! {
     pub trait Chunk<(T)>: const (), () {
+        /// Only covers initialized elements, see [`Chunk::set_init`].
         fn as_slice(&self) -> &[T];
         fn as_mut_slice(&mut self) -> &mut [T];
         fn as_ptr(&self) -> *const T;
         fn as_mut_ptr(&mut self) -> *mut T;
+        /// Marks the first `len` elements as initialized, like `BorrowedBuf::set_init`.
+        ///
+        /// # Safety
+        /// They have to be initialized.
+        #[inline(always)]
+        unsafe fn set_init(&mut self, _len: usize) {}
     }
 }
 
 declare_const_trait! {
-    pub trait Buf<(T)>: const (ReadBuf<T>, WriteBuf<T>), () {
+    pub trait Buf<(T)>: const (ReadBufMut<T>, WriteBuf<T>), () {
         fn clear(&mut self);
+        /// Moves the unread `pos..filled_pos` region to the front of the chunk.
+        fn compact(&mut self);
     }
 }
 
@@ -34,14 +46,21 @@ declare_const_trait! {
         fn filled_pos(&self) -> usize;
         unsafe fn set_filled_pos(&mut self, filled_pos: usize);
         fn capacity(&self) -> usize;
+        /// Continuous spare capacity after `filled_pos`, which can be shorter than
+        /// [`WriteBuf::remaining_space`] when it wraps around.
+        fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>];
+        /// Marks the first `len` elements of [`WriteBuf::spare_capacity_mut`] as filled.
+        ///
+        /// # Safety
+        /// They have to be initialized.
+        unsafe fn commit(&mut self, len: usize);
     }
 }
 
 declare_const_trait! {
-    pub trait ReadBuf<(T)>: const (), (Chunk<T>) {
+    pub trait ReadBuf<(T)>: const (), () {
         fn read(&mut self, len: usize) -> &[T];
         unsafe fn get_continuous(&self, len: usize) -> &[T];
-        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T];
         fn remaining(&self) -> usize;
         fn advance(&mut self, len: usize);
         fn pos(&self) -> usize;
@@ -49,30 +68,137 @@ declare_const_trait! {
     }
 }
 
+declare_const_trait! {
+    /// [`ReadBuf`] that owns its unread elements and can hand them out mutably, e.g. to unmask
+    /// a payload in place. Read-only views like [`SliceReader`](crate::SliceReader) don't
+    /// implement it.
+    pub trait ReadBufMut<(T)>: const (ReadBuf<T>), () {
+        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T];
+    }
+}
+
 pub trait ReadToBuf<T> {
+    /// Reads once into the spare capacity of `buf` and returns the number of elements read.
+    #[cfg(feature = "const-trait")]
+    fn read_to_buf(&mut self, buf: &mut impl const Buf<T>) -> Result<usize, ReadToBufError>;
+    /// Reads once into the spare capacity of `buf` and returns the number of elements read.
+    #[cfg(not(feature = "const-trait"))]
+    fn read_to_buf(&mut self, buf: &mut impl Buf<T>) -> Result<usize, ReadToBufError>;
+}
+
+pub trait WriteFromBuf<T> {
+    /// Writes once from the unread region of `buf`, advancing `pos` by the number of elements written.
     #[cfg(feature = "const-trait")]
-    fn read_to_buf(&mut self, buf: &mut impl const Buf<T>) -> Result<(), ()>;
+    fn write_from_buf(
+        &mut self,
+        buf: &mut impl const ReadBuf<T>,
+    ) -> Result<usize, WriteFromBufError>;
+    /// Writes once from the unread region of `buf`, advancing `pos` by the number of elements written.
     #[cfg(not(feature = "const-trait"))]
-    fn read_to_buf(&mut self, buf: &mut impl Buf<T>) -> Result<(), ()>;
+    fn write_from_buf(&mut self, buf: &mut impl ReadBuf<T>) -> Result<usize, WriteFromBufError>;
+    /// Writes until `buf` has nothing left to read. On error `pos` still reflects what was written.
+    #[cfg(feature = "const-trait")]
+    fn write_all_from_buf(
+        &mut self,
+        buf: &mut impl const ReadBuf<T>,
+    ) -> Result<usize, WriteFromBufError>;
+    /// Writes until `buf` has nothing left to read. On error `pos` still reflects what was written.
+    #[cfg(not(feature = "const-trait"))]
+    fn write_all_from_buf(&mut self, buf: &mut impl ReadBuf<T>)
+        -> Result<usize, WriteFromBufError>;
+}
+
+#[derive(Debug)]
+pub enum ReadToBufError {
+    /// The reader reached end of stream, e.g. the peer closed the connection.
+    Eof,
+    /// The reader has nothing to read right now.
+    WouldBlock,
+    /// The buffer has no spare capacity to read into.
+    BufferFull,
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
 }
 
 #[derive(Debug)]
+pub enum WriteFromBufError {
+    /// The writer can't accept anything right now.
+    WouldBlock,
+    /// The writer accepted zero bytes, e.g. the peer closed the connection.
+    WriteZero,
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl core::fmt::Display for ReadToBufError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReadToBufError::Eof => f.write_str("reached end of stream"),
+            ReadToBufError::WouldBlock => f.write_str("read would block"),
+            ReadToBufError::BufferFull => f.write_str("buffer is full"),
+            #[cfg(feature = "std")]
+            ReadToBufError::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadToBufError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadToBufError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for WriteFromBufError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WriteFromBufError::WouldBlock => f.write_str("write would block"),
+            WriteFromBufError::WriteZero => f.write_str("writer accepted zero bytes"),
+            #[cfg(feature = "std")]
+            WriteFromBufError::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WriteFromBufError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WriteFromBufError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WriteBufferError {
     BufferFull,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadBufferError {
+    BufferUnderflow,
+}
+
 declare_const_impl! {
     (impl<T, S: Buf<T>> Buf<T> for &mut S),
     (impl<T, S: const Buf<T> + const Chunk<T>> const Buf<T> for &mut S) {
         fn clear(&mut self) {
             self.deref_mut().clear()
         }
+
+        fn compact(&mut self) {
+            self.deref_mut().compact()
+        }
     }
 }
 
 declare_const_impl! {
     (impl<T, S: ReadBuf<T>> ReadBuf<T> for &mut S),
-    (impl<T, S: const ReadBuf<T> + const Chunk<T>> const ReadBuf<T> for &mut S) {
+    (impl<T, S: const ReadBuf<T>> const ReadBuf<T> for &mut S) {
         fn read(&mut self, len: usize) -> &[T] {
             self.deref_mut().read(len)
         }
@@ -81,10 +207,6 @@ declare_const_impl! {
             self.deref().get_continuous(len)
         }
 
-        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
-            self.deref_mut().get_continuous_mut(len)
-        }
-
         fn remaining(&self) -> usize {
             self.deref().remaining()
         }
@@ -103,6 +225,15 @@ declare_const_impl! {
     }
 }
 
+declare_const_impl! {
+    (impl<T, S: ReadBufMut<T>> ReadBufMut<T> for &mut S),
+    (impl<T, S: const ReadBufMut<T>> const ReadBufMut<T> for &mut S) {
+        unsafe fn get_continuous_mut(&mut self, len: usize) -> &mut [T] {
+            self.deref_mut().get_continuous_mut(len)
+        }
+    }
+}
+
 declare_const_impl! {
     (impl<T, S: WriteBuf<T>> WriteBuf<T> for &mut S),
     (impl<T, S: const WriteBuf<T> + const Chunk<T>> const WriteBuf<T> for &mut S) {
@@ -133,6 +264,14 @@ declare_const_impl! {
         fn capacity(&self) -> usize {
             self.deref().capacity()
         }
+
+        fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+            self.deref_mut().spare_capacity_mut()
+        }
+
+        unsafe fn commit(&mut self, len: usize) {
+            self.deref_mut().commit(len)
+        }
     }
 }
 
@@ -154,5 +293,9 @@ declare_const_impl! {
         fn as_mut_ptr(&mut self) ->  *mut T {
             self.deref_mut().as_mut_ptr()
         }
+
+        unsafe fn set_init(&mut self, len: usize) {
+            self.deref_mut().set_init(len)
+        }
     }
 }
@@ -0,0 +1,341 @@
+use crate::{ReadBuf, WriteBuf, WriteBufferError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarIntError {
+    /// The buffer ends before the last byte of the varint, nothing was consumed.
+    Incomplete,
+    /// The varint is longer than its type allows or its value doesn't fit the type.
+    Overlong,
+}
+
+pub const MAX_VAR_U32_LEN: usize = 5;
+pub const MAX_VAR_U64_LEN: usize = 10;
+
+#[inline(always)]
+pub const fn zigzag_encode_32(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+#[inline(always)]
+pub const fn zigzag_decode_32(value: u32) -> i32 {
+    (value >> 1) as i32 ^ -((value & 1) as i32)
+}
+
+#[inline(always)]
+pub const fn zigzag_encode_64(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[inline(always)]
+pub const fn zigzag_decode_64(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// Number of bytes the unsigned LEB128 encoding of `value` takes.
+#[inline(always)]
+pub const fn var_u64_len(value: u64) -> usize {
+    let bits = 64 - (value | 1).leading_zeros() as usize;
+    bits.div_ceil(7)
+}
+
+#[inline(always)]
+fn try_write_unsigned<W: WriteBuf<u8> + ?Sized>(
+    buf: &mut W,
+    mut value: u64,
+) -> Result<(), WriteBufferError> {
+    if value < 0x80 {
+        buf.try_write_fast(&[value as u8])
+    } else if value < 0x4000 {
+        buf.try_write_fast(&[value as u8 | 0x80, (value >> 7) as u8])
+    } else {
+        let mut bytes = [0; MAX_VAR_U64_LEN];
+        let mut len = 0;
+        while value >= 0x80 {
+            bytes[len] = value as u8 | 0x80;
+            value >>= 7;
+            len += 1;
+        }
+        bytes[len] = value as u8;
+        buf.try_write(&bytes[..len + 1])
+    }
+}
+
+#[inline(always)]
+fn try_write_signed<W: WriteBuf<u8> + ?Sized>(
+    buf: &mut W,
+    mut value: i64,
+) -> Result<(), WriteBufferError> {
+    if (-0x40..0x40).contains(&value) {
+        buf.try_write_fast(&[value as u8 & 0x7F])
+    } else {
+        let mut bytes = [0; MAX_VAR_U64_LEN];
+        let mut len = 0;
+        loop {
+            let byte = value as u8 & 0x7F;
+            value >>= 7;
+            if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                bytes[len] = byte;
+                break;
+            }
+            bytes[len] = byte | 0x80;
+            len += 1;
+        }
+        buf.try_write(&bytes[..len + 1])
+    }
+}
+
+/// Decodes an unsigned LEB128 of at most `MAX_LEN` bytes holding `BITS` bits.
+#[inline(always)]
+pub(crate) fn decode_unsigned<const MAX_LEN: usize, const BITS: u32>(
+    bytes: &[u8],
+) -> Result<(u64, usize), VarIntError> {
+    match bytes {
+        [first, ..] if *first < 0x80 => return Ok((*first as u64, 1)),
+        [first, second, ..] if *second < 0x80 => {
+            return Ok(((*first & 0x7F) as u64 | (*second as u64) << 7, 2))
+        }
+        _ => {}
+    }
+    let mut value = 0;
+    for (i, &byte) in bytes.iter().take(MAX_LEN).enumerate() {
+        if i == MAX_LEN - 1 && byte >= 1 << (BITS - 7 * (MAX_LEN as u32 - 1)) {
+            return Err(VarIntError::Overlong);
+        }
+        value |= ((byte & 0x7F) as u64) << (7 * i);
+        if byte < 0x80 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(VarIntError::Incomplete)
+}
+
+/// Decodes a signed LEB128 of at most `MAX_LEN` bytes holding `BITS` bits.
+#[inline(always)]
+fn decode_signed<const MAX_LEN: usize, const BITS: u32>(
+    bytes: &[u8],
+) -> Result<(i64, usize), VarIntError> {
+    if let [first, ..] = bytes {
+        if *first < 0x80 {
+            return Ok((((*first << 1) as i8 >> 1) as i64, 1));
+        }
+    }
+    let mut value = 0;
+    for (i, &byte) in bytes.iter().take(MAX_LEN).enumerate() {
+        let payload = byte & 0x7F;
+        if i == MAX_LEN - 1 {
+            let sign_bit = 1 << (BITS - 7 * (MAX_LEN as u32 - 1) - 1);
+            if byte >= 0x80 || (payload >= sign_bit && payload < 0x80 - sign_bit) {
+                return Err(VarIntError::Overlong);
+            }
+        }
+        value |= (payload as i64) << (7 * i);
+        if byte < 0x80 {
+            let shift = 7 * (i + 1);
+            if shift < 64 && payload & 0x40 != 0 {
+                value |= -1 << shift;
+            }
+            return Ok((value, i + 1));
+        }
+    }
+    Err(VarIntError::Incomplete)
+}
+
+/// Decodes from the readable region of `buf` and consumes the varint only on success.
+#[inline(always)]
+fn try_read_with<R: ReadBuf<u8> + ?Sized, V, const MAX_LEN: usize>(
+    buf: &mut R,
+    decode: impl Fn(&[u8]) -> Result<(V, usize), VarIntError>,
+) -> Result<V, VarIntError> {
+    let slice = unsafe { buf.get_continuous(MAX_LEN) };
+    let continuous_len = slice.len();
+    match decode(slice) {
+        Ok((value, len)) => {
+            buf.advance(len);
+            Ok(value)
+        }
+        Err(VarIntError::Incomplete) if buf.remaining() > continuous_len => {
+            // The readable region wraps, so gather it before decoding again.
+            let pos = buf.pos();
+            let mut bytes = [0; MAX_VAR_U64_LEN];
+            let mut len = 0;
+            while len < MAX_LEN {
+                let slice = buf.read(MAX_LEN - len);
+                if slice.is_empty() {
+                    break;
+                }
+                bytes[len..len + slice.len()].copy_from_slice(slice);
+                len += slice.len();
+            }
+            unsafe { buf.set_pos(pos) };
+            let (value, len) = decode(&bytes[..len])?;
+            buf.advance(len);
+            Ok(value)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+macro_rules! read_methods {
+    ($($ty:ty, $max_len:expr, $decode:expr => $try_read:ident, $read:ident;)*) => {
+        $(
+            #[inline(always)]
+            fn $try_read(&mut self) -> Result<$ty, VarIntError> {
+                try_read_with::<_, _, { $max_len }>(self, $decode)
+            }
+
+            #[inline(always)]
+            fn $read(&mut self) -> $ty {
+                self.$try_read().expect("invalid varint")
+            }
+        )*
+    };
+}
+
+macro_rules! write_methods {
+    ($($ty:ty, $encode:expr => $try_write:ident, $write:ident;)*) => {
+        $(
+            #[inline(always)]
+            fn $try_write(&mut self, value: $ty) -> Result<(), WriteBufferError> {
+                $encode(self, value)
+            }
+
+            #[inline(always)]
+            fn $write(&mut self, value: $ty) {
+                self.$try_write(value).expect("buffer full")
+            }
+        )*
+    };
+}
+
+/// LEB128 decoders for byte buffers.
+///
+/// `var_i32`/`var_i64` read the two's complement bits as unsigned LEB128 like Minecraft's
+/// VarInt/VarLong, `zigzag_*` read Protobuf's `sint32`/`sint64` and `sleb128_*` read signed LEB128.
+pub trait ReadVarInt: ReadBuf<u8> {
+    read_methods! {
+        u32, MAX_VAR_U32_LEN, |bytes: &[u8]| decode_unsigned::<MAX_VAR_U32_LEN, 32>(bytes).map(|(v, len)| (v as u32, len))
+            => try_read_var_u32, read_var_u32;
+        u64, MAX_VAR_U64_LEN, decode_unsigned::<MAX_VAR_U64_LEN, 64>
+            => try_read_var_u64, read_var_u64;
+        i32, MAX_VAR_U32_LEN, |bytes: &[u8]| decode_unsigned::<MAX_VAR_U32_LEN, 32>(bytes).map(|(v, len)| (v as u32 as i32, len))
+            => try_read_var_i32, read_var_i32;
+        i64, MAX_VAR_U64_LEN, |bytes: &[u8]| decode_unsigned::<MAX_VAR_U64_LEN, 64>(bytes).map(|(v, len)| (v as i64, len))
+            => try_read_var_i64, read_var_i64;
+        i32, MAX_VAR_U32_LEN, |bytes: &[u8]| decode_unsigned::<MAX_VAR_U32_LEN, 32>(bytes).map(|(v, len)| (zigzag_decode_32(v as u32), len))
+            => try_read_zigzag_i32, read_zigzag_i32;
+        i64, MAX_VAR_U64_LEN, |bytes: &[u8]| decode_unsigned::<MAX_VAR_U64_LEN, 64>(bytes).map(|(v, len)| (zigzag_decode_64(v), len))
+            => try_read_zigzag_i64, read_zigzag_i64;
+        i32, MAX_VAR_U32_LEN, |bytes: &[u8]| decode_signed::<MAX_VAR_U32_LEN, 32>(bytes).map(|(v, len)| (v as i32, len))
+            => try_read_sleb128_i32, read_sleb128_i32;
+        i64, MAX_VAR_U64_LEN, decode_signed::<MAX_VAR_U64_LEN, 64>
+            => try_read_sleb128_i64, read_sleb128_i64;
+    }
+}
+
+impl<R: ReadBuf<u8> + ?Sized> ReadVarInt for R {}
+
+/// LEB128 encoders for byte buffers, see [`ReadVarInt`] for the flavours.
+pub trait WriteVarInt: WriteBuf<u8> {
+    write_methods! {
+        u32, |buf: &mut Self, value: u32| try_write_unsigned(buf, value as u64)
+            => try_write_var_u32, write_var_u32;
+        u64, try_write_unsigned
+            => try_write_var_u64, write_var_u64;
+        i32, |buf: &mut Self, value: i32| try_write_unsigned(buf, value as u32 as u64)
+            => try_write_var_i32, write_var_i32;
+        i64, |buf: &mut Self, value: i64| try_write_unsigned(buf, value as u64)
+            => try_write_var_i64, write_var_i64;
+        i32, |buf: &mut Self, value: i32| try_write_unsigned(buf, zigzag_encode_32(value) as u64)
+            => try_write_zigzag_i32, write_zigzag_i32;
+        i64, |buf: &mut Self, value: i64| try_write_unsigned(buf, zigzag_encode_64(value))
+            => try_write_zigzag_i64, write_zigzag_i64;
+        i32, |buf: &mut Self, value: i32| try_write_signed(buf, value as i64)
+            => try_write_sleb128_i32, write_sleb128_i32;
+        i64, try_write_signed
+            => try_write_sleb128_i64, write_sleb128_i64;
+    }
+}
+
+impl<W: WriteBuf<u8> + ?Sized> WriteVarInt for W {}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::{Buf, Buffer, RingBuffer};
+
+    use super::*;
+
+    #[test]
+    fn test_unsigned_round_trip() {
+        let mut buffer: Buffer<[u8; 64]> = Buffer::new();
+        for value in [0, 1, 127, 128, 300, 16383, 16384, u32::MAX as u64, u64::MAX] {
+            buffer.write_var_u64(value);
+            assert_eq!(buffer.remaining(), var_u64_len(value));
+            assert_eq!(buffer.read_var_u64(), value);
+        }
+        buffer.write_var_u32(300);
+        assert_eq!(unsafe { buffer.get_continuous(2) }, &[0xAC, 0x02]);
+        assert_eq!(buffer.read_var_u32(), 300);
+    }
+
+    #[test]
+    fn test_signed_round_trip() {
+        let mut buffer: Buffer<[u8; 256]> = Buffer::new();
+        buffer.write_var_i32(-1);
+        assert_eq!(buffer.remaining(), 5);
+        assert_eq!(buffer.read_var_i32(), -1);
+
+        for value in [0, 1, -1, 63, -64, 64, -65, i32::MIN, i32::MAX] {
+            buffer.write_zigzag_i32(value);
+            assert_eq!(buffer.read_zigzag_i32(), value);
+            buffer.write_sleb128_i32(value);
+            assert_eq!(buffer.read_sleb128_i32(), value);
+        }
+        for value in [0, -1, i64::MIN, i64::MAX] {
+            buffer.write_var_i64(value);
+            assert_eq!(buffer.read_var_i64(), value);
+            buffer.write_zigzag_i64(value);
+            assert_eq!(buffer.read_zigzag_i64(), value);
+            buffer.write_sleb128_i64(value);
+            assert_eq!(buffer.read_sleb128_i64(), value);
+        }
+        buffer.write_sleb128_i64(-123456);
+        assert_eq!(unsafe { buffer.get_continuous(3) }, &[0xC0, 0xBB, 0x78]);
+        assert_eq!(zigzag_encode_32(-1), 1);
+        assert_eq!(zigzag_encode_64(1), 2);
+    }
+
+    #[test]
+    fn test_incomplete_consumes_nothing() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(&[0x80, 0x80]);
+        assert_eq!(buffer.try_read_var_u32(), Err(VarIntError::Incomplete));
+        assert_eq!(buffer.remaining(), 2);
+        buffer.write(&[0x01]);
+        assert_eq!(buffer.try_read_var_u32(), Ok(1 << 14));
+    }
+
+    #[test]
+    fn test_overlong() {
+        let mut buffer: Buffer<[u8; 16]> = Buffer::new();
+        buffer.write(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]);
+        assert_eq!(buffer.try_read_var_u32(), Err(VarIntError::Overlong));
+        buffer.clear();
+        buffer.write(&[0x80; 6]);
+        assert_eq!(buffer.try_read_var_u32(), Err(VarIntError::Overlong));
+        assert_eq!(buffer.remaining(), 6);
+    }
+
+    #[test]
+    fn test_read_across_ring_wrap() {
+        let mut buffer: RingBuffer<[u8; 8]> = RingBuffer::new();
+        buffer.write(&[0; 6]);
+        buffer.advance(6);
+        buffer.write_var_u64(1 << 30);
+        assert_eq!(buffer.try_read_var_u64(), Ok(1 << 30));
+
+        buffer.write(&[0x80]);
+        assert_eq!(buffer.try_read_var_u64(), Err(VarIntError::Incomplete));
+        assert_eq!(buffer.remaining(), 1);
+    }
+}
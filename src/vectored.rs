@@ -0,0 +1,399 @@
+use core::ptr::slice_from_raw_parts_mut;
+use std::{
+    alloc::Allocator,
+    io::{self, IoSlice, IoSliceMut},
+};
+
+use crate::{
+    const_min, ring::physical, BufIndex, Buffer, Chunk, ChunkBuilder, ReadBuf, ReadToBufError,
+    RingBuffer, WriteBuf, WriteFromBufError,
+};
+
+/// Upper bound on the number of slices handed to a single vectored call.
+pub const MAX_IO_SLICES: usize = 64;
+
+/// Byte buffers that expose their readable region and spare capacity as I/O slices.
+///
+/// Each region is at most two slices, the second one is empty unless the region wraps.
+/// The trait is object safe, so buffers of different sizes can be passed together as
+/// `&mut dyn VectoredBuf`.
+pub trait VectoredBuf {
+    fn io_slices(&self) -> [IoSlice<'_>; 2];
+    fn io_slices_mut(&mut self) -> [IoSliceMut<'_>; 2];
+    /// Marks `len` bytes of the readable region as consumed.
+    fn advance_read(&mut self, len: usize);
+    /// Marks `len` bytes of the spare capacity as filled.
+    fn advance_write(&mut self, len: usize);
+}
+
+impl<S: VectoredBuf + ?Sized> VectoredBuf for &mut S {
+    #[inline(always)]
+    fn io_slices(&self) -> [IoSlice<'_>; 2] {
+        (**self).io_slices()
+    }
+
+    #[inline(always)]
+    fn io_slices_mut(&mut self) -> [IoSliceMut<'_>; 2] {
+        (**self).io_slices_mut()
+    }
+
+    #[inline(always)]
+    fn advance_read(&mut self, len: usize) {
+        (**self).advance_read(len)
+    }
+
+    #[inline(always)]
+    fn advance_write(&mut self, len: usize) {
+        (**self).advance_write(len)
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> VectoredBuf
+    for Buffer<[u8; N], A, C, L>
+{
+    #[inline(always)]
+    fn io_slices(&self) -> [IoSlice<'_>; 2] {
+        [
+            IoSlice::new(unsafe { self.get_continuous(self.remaining()) }),
+            IoSlice::new(&[]),
+        ]
+    }
+
+    #[inline(always)]
+    fn io_slices_mut(&mut self) -> [IoSliceMut<'_>; 2] {
+        let filled_pos = self.filled_pos.to_usize();
+        init_chunk(&mut self.chunk, N);
+        let spare = unsafe {
+            &mut *slice_from_raw_parts_mut(
+                self.chunk.as_mut_ptr().wrapping_add(filled_pos),
+                N - filled_pos,
+            )
+        };
+        [IoSliceMut::new(spare), IoSliceMut::new(&mut [])]
+    }
+
+    #[inline(always)]
+    fn advance_read(&mut self, len: usize) {
+        self.advance(len)
+    }
+
+    #[inline(always)]
+    fn advance_write(&mut self, len: usize) {
+        assert!(
+            len <= self.remaining_space(),
+            "cannot advance past capacity"
+        );
+        unsafe { self.commit(len) };
+    }
+}
+
+impl<const N: usize, A: Allocator, C: ChunkBuilder<A> + Chunk<u8>, L: BufIndex> VectoredBuf
+    for RingBuffer<[u8; N], A, C, L>
+{
+    #[inline(always)]
+    fn io_slices(&self) -> [IoSlice<'_>; 2] {
+        let (first, second) = self.as_slices();
+        [IoSlice::new(first), IoSlice::new(second)]
+    }
+
+    #[inline(always)]
+    fn io_slices_mut(&mut self) -> [IoSliceMut<'_>; 2] {
+        let index = physical::<N>(self.filled_pos.to_usize());
+        let spare_len = self.remaining_space();
+        let first_len = const_min!(spare_len, N - index);
+        // The second slice lies before `index`, which is always initialized.
+        init_chunk(&mut self.chunk, index + first_len);
+        let ptr = self.chunk.as_mut_ptr();
+        unsafe {
+            [
+                IoSliceMut::new(&mut *slice_from_raw_parts_mut(
+                    ptr.wrapping_add(index),
+                    first_len,
+                )),
+                IoSliceMut::new(&mut *slice_from_raw_parts_mut(ptr, spare_len - first_len)),
+            ]
+        }
+    }
+
+    #[inline(always)]
+    fn advance_read(&mut self, len: usize) {
+        self.advance(len)
+    }
+
+    #[inline(always)]
+    fn advance_write(&mut self, len: usize) {
+        assert!(
+            len <= self.remaining_space(),
+            "cannot advance past capacity"
+        );
+        unsafe { self.commit(len) };
+    }
+}
+
+/// Zeroes the chunk up to `len` where it isn't initialized yet, as [`IoSliceMut`] needs
+/// initialized memory.
+#[inline(always)]
+fn init_chunk<C: Chunk<u8>>(chunk: &mut C, len: usize) {
+    let init = chunk.as_slice().len();
+    if init < len {
+        unsafe {
+            chunk.as_mut_ptr().add(init).write_bytes(0, len - init);
+            chunk.set_init(len);
+        }
+    }
+}
+
+/// Reads into the spare capacity of several buffers with a single vectored call.
+pub trait ReadVectoredToBuf {
+    /// Fills `bufs` in order and returns the number of bytes read.
+    ///
+    /// Retries on [`io::ErrorKind::Interrupted`].
+    fn read_vectored_to_bufs(
+        &mut self,
+        bufs: &mut [impl VectoredBuf],
+    ) -> Result<usize, ReadToBufError>;
+}
+
+impl<S: io::Read> ReadVectoredToBuf for S {
+    #[inline(always)]
+    fn read_vectored_to_bufs(
+        &mut self,
+        bufs: &mut [impl VectoredBuf],
+    ) -> Result<usize, ReadToBufError> {
+        read_with(bufs, |slices| self.read_vectored(slices))
+    }
+}
+
+/// Writes the readable regions of several buffers with a single vectored call.
+pub trait WriteVectoredFromBuf {
+    /// Drains `bufs` in order and returns the number of bytes written.
+    ///
+    /// Retries on [`io::ErrorKind::Interrupted`].
+    fn write_vectored_from_bufs(
+        &mut self,
+        bufs: &mut [impl VectoredBuf],
+    ) -> Result<usize, WriteFromBufError>;
+}
+
+impl<S: io::Write> WriteVectoredFromBuf for S {
+    #[inline(always)]
+    fn write_vectored_from_bufs(
+        &mut self,
+        bufs: &mut [impl VectoredBuf],
+    ) -> Result<usize, WriteFromBufError> {
+        write_with(bufs, |slices| self.write_vectored(slices))
+    }
+}
+
+/// Same as [`ReadVectoredToBuf::read_vectored_to_bufs`] but calls `readv(2)` directly on `fd`.
+#[cfg(target_os = "linux")]
+pub fn readv_to_bufs(
+    fd: std::os::fd::BorrowedFd<'_>,
+    bufs: &mut [impl VectoredBuf],
+) -> Result<usize, ReadToBufError> {
+    use std::os::fd::AsRawFd;
+
+    read_with(bufs, |slices| {
+        let result = unsafe {
+            libc::readv(
+                fd.as_raw_fd(),
+                slices.as_ptr() as *const libc::iovec,
+                slices.len() as _,
+            )
+        };
+        cvt(result)
+    })
+}
+
+/// Same as [`WriteVectoredFromBuf::write_vectored_from_bufs`] but calls `writev(2)` directly on `fd`.
+#[cfg(target_os = "linux")]
+pub fn writev_from_bufs(
+    fd: std::os::fd::BorrowedFd<'_>,
+    bufs: &mut [impl VectoredBuf],
+) -> Result<usize, WriteFromBufError> {
+    use std::os::fd::AsRawFd;
+
+    write_with(bufs, |slices| {
+        let result = unsafe {
+            libc::writev(
+                fd.as_raw_fd(),
+                slices.as_ptr() as *const libc::iovec,
+                slices.len() as _,
+            )
+        };
+        cvt(result)
+    })
+}
+
+/// Turns the result of `readv(2)`/`writev(2)` into an [`io::Result`].
+///
+/// [`IoSlice`] and [`IoSliceMut`] are ABI compatible with `struct iovec`, so the slices are
+/// passed to libc as they are.
+#[cfg(target_os = "linux")]
+#[inline(always)]
+fn cvt(result: isize) -> io::Result<usize> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+#[inline(always)]
+fn read_with(
+    bufs: &mut [impl VectoredBuf],
+    mut read: impl FnMut(&mut [IoSliceMut<'_>]) -> io::Result<usize>,
+) -> Result<usize, ReadToBufError> {
+    let mut slices: [IoSliceMut<'_>; MAX_IO_SLICES] =
+        core::array::from_fn(|_| IoSliceMut::new(&mut []));
+    let mut count = 0;
+    'outer: for buf in bufs.iter_mut() {
+        for slice in buf.io_slices_mut() {
+            if count == MAX_IO_SLICES {
+                break 'outer;
+            }
+            if !slice.is_empty() {
+                slices[count] = slice;
+                count += 1;
+            }
+        }
+    }
+    if count == 0 {
+        return Err(ReadToBufError::BufferFull);
+    }
+    let read_length = loop {
+        match read(&mut slices[..count]) {
+            Ok(0) => return Err(ReadToBufError::Eof),
+            Ok(read_length) => break read_length,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                return Err(ReadToBufError::WouldBlock)
+            }
+            Err(err) => return Err(ReadToBufError::Io(err)),
+        }
+    };
+    let mut left = read_length;
+    for buf in bufs.iter_mut() {
+        let [first, second] = buf.io_slices_mut();
+        let len = const_min!(left, first.len() + second.len());
+        buf.advance_write(len);
+        left -= len;
+    }
+    Ok(read_length)
+}
+
+#[inline(always)]
+fn write_with(
+    bufs: &mut [impl VectoredBuf],
+    mut write: impl FnMut(&[IoSlice<'_>]) -> io::Result<usize>,
+) -> Result<usize, WriteFromBufError> {
+    let mut slices: [IoSlice<'_>; MAX_IO_SLICES] = [IoSlice::new(&[]); MAX_IO_SLICES];
+    let mut count = 0;
+    'outer: for buf in bufs.iter() {
+        for slice in buf.io_slices() {
+            if count == MAX_IO_SLICES {
+                break 'outer;
+            }
+            if !slice.is_empty() {
+                slices[count] = slice;
+                count += 1;
+            }
+        }
+    }
+    if count == 0 {
+        return Ok(0);
+    }
+    let write_length = loop {
+        match write(&slices[..count]) {
+            Ok(0) => return Err(WriteFromBufError::WriteZero),
+            Ok(write_length) => break write_length,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                return Err(WriteFromBufError::WouldBlock)
+            }
+            Err(err) => return Err(WriteFromBufError::Io(err)),
+        }
+    };
+    let mut left = write_length;
+    for buf in bufs.iter_mut() {
+        let [first, second] = buf.io_slices();
+        let len = const_min!(left, first.len() + second.len());
+        buf.advance_read(len);
+        left -= len;
+    }
+    Ok(write_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Buf;
+
+    #[test]
+    fn test_write_vectored() {
+        let mut header: Buffer<[u8; 8]> = Buffer::new();
+        header.write(b"HEAD");
+        let mut body: Buffer<[u8; 32]> = Buffer::new();
+        body.write(b"body");
+        let mut sink = Vec::new();
+
+        let mut bufs: [&mut dyn VectoredBuf; 2] = [&mut header, &mut body];
+        assert_eq!(sink.write_vectored_from_bufs(&mut bufs).unwrap(), 8);
+        assert_eq!(sink, b"HEADbody");
+        assert_eq!(header.remaining() + body.remaining(), 0);
+        assert_eq!(
+            sink.write_vectored_from_bufs(&mut [&mut header]).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_ring_halves() {
+        let mut ring: RingBuffer<[u8; 8]> = RingBuffer::new();
+        ring.write(b"abcdef");
+        ring.advance(4);
+        let mut source = &b"ghijklmn"[..];
+        assert_eq!(source.read_vectored_to_bufs(&mut [&mut ring]).unwrap(), 6);
+        assert_eq!(ring.as_slices(), (&b"efgh"[..], &b"ijkl"[..]));
+        assert!(matches!(
+            source.read_vectored_to_bufs(&mut [&mut ring]),
+            Err(ReadToBufError::BufferFull)
+        ));
+
+        let mut sink = Vec::new();
+        assert_eq!(sink.write_vectored_from_bufs(&mut [&mut ring]).unwrap(), 8);
+        assert_eq!(sink, b"efghijkl");
+        assert_eq!(ring.remaining(), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_readv_writev() {
+        use std::os::fd::AsFd;
+
+        let (reader, writer) = io::pipe().unwrap();
+        let mut first: Buffer<[u8; 4]> = Buffer::new();
+        first.write(b"abcd");
+        let mut second: Buffer<[u8; 4]> = Buffer::new();
+        second.write(b"ef");
+        assert_eq!(
+            writev_from_bufs(writer.as_fd(), &mut [&mut first, &mut second]).unwrap(),
+            6
+        );
+
+        first.clear();
+        second.clear();
+        let mut ring: RingBuffer<[u8; 4]> = RingBuffer::new();
+        assert_eq!(
+            readv_to_bufs(reader.as_fd(), &mut [&mut first, &mut second]).unwrap(),
+            6
+        );
+        assert_eq!(first.read(4), b"abcd");
+        assert_eq!(second.read(4), b"ef");
+        drop(writer);
+        assert!(matches!(
+            readv_to_bufs(reader.as_fd(), &mut [&mut ring]),
+            Err(ReadToBufError::Eof)
+        ));
+    }
+}